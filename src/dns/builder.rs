@@ -0,0 +1,223 @@
+#![allow(clippy::needless_return)]
+
+use super::{
+    model::{
+        DnsClass, DnsHeaderOpecode, DnsHeaderRcode, DnsHeaderSection, DnsMessage,
+        DnsQuestionSectionEntry, DnsType, Edns,
+    },
+    name::DomainName,
+};
+
+/// Builds query messages. Every flag is cleared unless set explicitly.
+///
+/// ```
+/// use rust_dns::dns::*;
+///
+/// let query = QueryBuilder::new(0x1234)
+///     .question(&"example.com.".parse().unwrap(), DnsType::TXT, DnsClass::CH)
+///     .cd(true)
+///     .build();
+/// assert_eq!(query.header.qdcount, 1);
+/// assert_eq!(query.to_wire().unwrap().len(), 12 + 13 + 4);
+/// ```
+#[derive(Clone)]
+pub struct QueryBuilder {
+    id: u16,
+    questions: Vec<DnsQuestionSectionEntry>,
+    rd: bool,
+    ad: bool,
+    cd: bool,
+    edns: Option<Edns>,
+    randomize_case: bool,
+}
+impl QueryBuilder {
+    pub fn new(id: u16) -> Self {
+        return QueryBuilder {
+            id,
+            questions: Vec::new(),
+            rd: false,
+            ad: false,
+            cd: false,
+            edns: None,
+            randomize_case: false,
+        };
+    }
+
+    pub fn id(mut self, id: u16) -> Self {
+        self.id = id;
+        return self;
+    }
+
+    pub fn question(mut self, q_name: &DomainName, q_type: DnsType, q_class: DnsClass) -> Self {
+        self.questions.push(DnsQuestionSectionEntry {
+            q_name: q_name.clone(),
+            q_type,
+            q_class,
+        });
+        return self;
+    }
+
+    pub fn rd(mut self, rd: bool) -> Self {
+        self.rd = rd;
+        return self;
+    }
+
+    pub fn ad(mut self, ad: bool) -> Self {
+        self.ad = ad;
+        return self;
+    }
+
+    pub fn cd(mut self, cd: bool) -> Self {
+        self.cd = cd;
+        return self;
+    }
+
+    pub fn edns(mut self, edns: Edns) -> Self {
+        self.edns = Some(edns);
+        return self;
+    }
+
+    // the case of the letters in the names of the questions is randomized on every build
+    pub fn randomize_case(mut self, randomize_case: bool) -> Self {
+        self.randomize_case = randomize_case;
+        return self;
+    }
+
+    pub fn questions(&self) -> &[DnsQuestionSectionEntry] {
+        return &self.questions;
+    }
+
+    pub fn build(self) -> DnsMessage {
+        let questions: Vec<DnsQuestionSectionEntry> = if self.randomize_case {
+            self.questions
+                .iter()
+                .map(|question| DnsQuestionSectionEntry {
+                    q_name: question.q_name.randomize_case(),
+                    ..question.clone()
+                })
+                .collect()
+        } else {
+            self.questions
+        };
+
+        return DnsMessage {
+            header: DnsHeaderSection {
+                id: self.id,
+                qr: false,
+                opcode: DnsHeaderOpecode::Query,
+                aa: false,
+                tc: false,
+                rd: self.rd,
+                ra: false,
+                z: false,
+                ad: self.ad,
+                cd: self.cd,
+                rcode: DnsHeaderRcode::NoError,
+                qdcount: questions.len() as u16,
+                ancount: 0,
+                nscount: 0,
+                arcount: self.edns.is_some() as u16,
+            },
+            questions,
+            answers: Vec::new(),
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+            edns: self.edns,
+        };
+    }
+}
+#[cfg(test)]
+mod query_builder {
+    use crate::dns::{
+        builder::QueryBuilder,
+        model::{DnsClass, DnsType, Edns},
+        parser::parse_dns_message,
+    };
+
+    #[test]
+    fn case1() {
+        let bytes = QueryBuilder::new(1)
+            .question(&"example.com.".parse().unwrap(), DnsType::A, DnsClass::IN)
+            .edns(Edns {
+                udp_payload_size: 1232,
+                extended_rcode: 0,
+                version: 0,
+                dnssec_ok: false,
+                options: vec![],
+            })
+            .build()
+            .to_wire()
+            .unwrap();
+
+        assert_eq!(
+            bytes,
+            vec![
+                0x00, 0x01, // id
+                0x00, // !qr opecode=Query !aa !tc !rd
+                0x00, // !ra Z !ad !cd rcode=NoError
+                0x00, 0x01, // qdcount
+                0x00, 0x00, // ancount
+                0x00, 0x00, // nscount
+                0x00, 0x01, // arcount
+                // questions
+                7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0, // qname
+                0x00, 0x01, // qtype
+                0x00, 0x01, // qclass
+                // additionals
+                0x00, 0x00, 0x29, 0x04, 0xd0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // ROOT OPT
+            ]
+        );
+    }
+
+    #[test]
+    fn case2() {
+        let bytes = QueryBuilder::new(2)
+            .question(
+                &"version.bind.".parse().unwrap(),
+                DnsType::TXT,
+                DnsClass::CH,
+            )
+            .question(&"example.com.".parse().unwrap(), DnsType::ANY, DnsClass::IN)
+            .rd(true)
+            .ad(true)
+            .cd(true)
+            .build()
+            .to_wire()
+            .unwrap();
+
+        assert_eq!(bytes[2..4], [0b00000001, 0b00110000]); // rd, ad cd
+
+        let message = parse_dns_message(&bytes).unwrap();
+        assert!(message.header.rd && message.header.ad && message.header.cd);
+        assert_eq!(message.questions.len(), 2);
+        assert_eq!(message.questions[0].q_type, DnsType::TXT);
+        assert_eq!(message.questions[0].q_class, DnsClass::CH);
+        assert_eq!(message.questions[1].q_name.to_string(), "example.com.");
+        assert_eq!(message.questions[1].q_type, DnsType::ANY);
+        assert_eq!(message.edns, None);
+    }
+
+    #[test]
+    fn case3() {
+        let name = "www.example.com.".parse().unwrap();
+        let builder = QueryBuilder::new(3)
+            .question(&name, DnsType::A, DnsClass::IN)
+            .randomize_case(true);
+
+        let names: Vec<String> = (0..16)
+            .map(|_| builder.clone().build().questions[0].q_name.to_string())
+            .collect();
+        assert!(
+            names
+                .iter()
+                .all(|q_name| q_name.eq_ignore_ascii_case("www.example.com."))
+        );
+        assert!(names.iter().any(|q_name| q_name != "www.example.com."));
+        assert_eq!(
+            builder.randomize_case(false).build().questions[0]
+                .q_name
+                .to_string(),
+            "www.example.com."
+        );
+    }
+}
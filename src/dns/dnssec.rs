@@ -0,0 +1,1341 @@
+#![allow(clippy::needless_return)]
+
+use core::fmt;
+
+use ring::{digest, signature};
+
+use super::{
+    encoding::parse_base32hex,
+    error::DnsSerializeError,
+    model::{DnsHeaderRcode, DnsMessage, DnsResourceRecord, DnsType, RData},
+    name::DomainName,
+};
+
+// see: https://www.rfc-editor.org/rfc/rfc4034#section-2.1.1
+const DNSKEY_FLAG_ZONE: u16 = 0b00000001_00000000;
+const DNSKEY_FLAG_REVOKE: u16 = 0b00000000_10000000;
+const DNSKEY_PROTOCOL: u8 = 3;
+// see: https://www.rfc-editor.org/rfc/rfc5155#section-3.1.2
+const NSEC3_FLAG_OPT_OUT: u8 = 0b00000001;
+const NSEC3_HASH_SHA1: u8 = 1;
+
+// see: https://www.rfc-editor.org/rfc/rfc4033#section-5
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ValidationStatus {
+    Secure,
+    Insecure,
+    Bogus,
+    Indeterminate,
+}
+impl ValidationStatus {
+    // an answer is only as good as the weakest part it is made of
+    pub fn combine(self, other: ValidationStatus) -> ValidationStatus {
+        let rank = |status| match status {
+            ValidationStatus::Secure => 0,
+            ValidationStatus::Insecure => 1,
+            ValidationStatus::Indeterminate => 2,
+            ValidationStatus::Bogus => 3,
+        };
+
+        return if rank(self) >= rank(other) {
+            self
+        } else {
+            other
+        };
+    }
+}
+impl fmt::Display for ValidationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationStatus::Secure => write!(f, "Secure"),
+            ValidationStatus::Insecure => write!(f, "Insecure"),
+            ValidationStatus::Bogus => write!(f, "Bogus"),
+            ValidationStatus::Indeterminate => write!(f, "Indeterminate"),
+        }
+    }
+}
+
+// the DNSKEY RRset of a zone, validated from a trust anchor
+#[derive(PartialEq, Debug, Clone)]
+pub struct ZoneKeys {
+    pub zone: DomainName,
+    pub dnskeys: Vec<RData>,
+}
+
+// see: https://www.rfc-editor.org/rfc/rfc4034#appendix-B
+pub fn compute_key_tag(dnskey: &RData) -> u16 {
+    // a DNSKEY has no length bytes to overflow
+    let mut sum = dnskey
+        .to_wire()
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .map(|(index, &x)| {
+            if index % 2 == 0 {
+                (x as u32) << 8
+            } else {
+                x as u32
+            }
+        })
+        .sum::<u32>();
+    sum += sum >> 16;
+
+    return sum as u16;
+}
+#[cfg(test)]
+mod compute_key_tag {
+    use crate::dns::{dnssec::compute_key_tag, model::RData};
+
+    #[test]
+    fn case1() {
+        // from rfc8080 6.1
+        let dnskey = RData::DNSKEY {
+            flags: 257,
+            protocol: 3,
+            algorithm: 15,
+            public_key: vec![
+                0x97, 0x4d, 0x96, 0xa2, 0x2d, 0x22, 0x4b, 0xc0, 0x1a, 0xdb, 0x91, 0x50, 0x91, 0x47,
+                0x7d, 0x44, 0xcc, 0xd9, 0x1c, 0x9a, 0x41, 0xa1, 0x14, 0x30, 0x01, 0x01, 0x17, 0xd5,
+                0x2c, 0x59, 0x24, 0x0e,
+            ],
+        };
+
+        assert_eq!(compute_key_tag(&dnskey), 3613);
+    }
+}
+
+// None when the digest type is not supported
+pub fn compute_ds_digest(owner: &DomainName, dnskey: &RData, digest_type: u8) -> Option<Vec<u8>> {
+    let algorithm = match digest_type {
+        1 => &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        2 => &digest::SHA256,
+        4 => &digest::SHA384,
+        _ => return None,
+    };
+
+    let mut data = owner.to_lowercase().to_wire();
+    data.extend_from_slice(&dnskey.to_wire().ok()?);
+
+    return Some(digest::digest(algorithm, &data).as_ref().to_vec());
+}
+
+fn is_supported_algorithm(algorithm: u8) -> bool {
+    // RSASHA256, ECDSAP256SHA256 and ED25519
+    return matches!(algorithm, 8 | 13 | 15);
+}
+
+// see: https://www.rfc-editor.org/rfc/rfc4034#section-6.2
+fn to_canonical_rdata(rdata: &RData) -> RData {
+    return match rdata {
+        RData::NS(name) => RData::NS(name.to_lowercase()),
+        RData::CNAME(name) => RData::CNAME(name.to_lowercase()),
+        RData::SOA {
+            mname,
+            rname,
+            serial,
+            refresh,
+            retry,
+            expire,
+            minimum,
+        } => RData::SOA {
+            mname: mname.to_lowercase(),
+            rname: rname.to_lowercase(),
+            serial: *serial,
+            refresh: *refresh,
+            retry: *retry,
+            expire: *expire,
+            minimum: *minimum,
+        },
+        RData::PTR(name) => RData::PTR(name.to_lowercase()),
+        RData::MX {
+            preference,
+            exchange,
+        } => RData::MX {
+            preference: *preference,
+            exchange: exchange.to_lowercase(),
+        },
+        RData::SRV {
+            priority,
+            weight,
+            port,
+            target,
+        } => RData::SRV {
+            priority: *priority,
+            weight: *weight,
+            port: *port,
+            target: target.to_lowercase(),
+        },
+        _ => rdata.clone(),
+    };
+}
+
+// the data an RRSIG signs. the RRset must not be empty.
+// see: https://www.rfc-editor.org/rfc/rfc4034#section-3.1.8.1
+pub(super) fn create_signed_data(rrset: &[&DnsResourceRecord], rrsig: &RData) -> Option<Vec<u8>> {
+    let RData::RRSIG {
+        type_covered,
+        algorithm,
+        labels,
+        original_ttl,
+        expiration,
+        inception,
+        key_tag,
+        signer_name,
+        ..
+    } = rrsig
+    else {
+        return None;
+    };
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&u16::from(*type_covered).to_be_bytes());
+    data.push(*algorithm);
+    data.push(*labels);
+    data.extend_from_slice(&original_ttl.to_be_bytes());
+    data.extend_from_slice(&expiration.to_be_bytes());
+    data.extend_from_slice(&inception.to_be_bytes());
+    data.extend_from_slice(&key_tag.to_be_bytes());
+    data.extend_from_slice(&signer_name.to_lowercase().to_wire());
+
+    // a wildcard expansion is signed as the wildcard itself.
+    // see: https://www.rfc-editor.org/rfc/rfc4035#section-5.3.2
+    let owner = rrset[0].name.to_lowercase();
+    let owner_labels: Vec<Vec<u8>> = owner.labels().map(|label| label.to_vec()).collect();
+    let owner = if (*labels as usize) < owner_labels.len() {
+        let mut wildcard_labels = vec![b"*".to_vec()];
+        wildcard_labels.extend_from_slice(&owner_labels[owner_labels.len() - *labels as usize..]);
+        DomainName::from_labels(wildcard_labels).ok()?
+    } else {
+        owner
+    };
+
+    let mut rdatas: Vec<Vec<u8>> = rrset
+        .iter()
+        .map(|rr| to_canonical_rdata(&rr.rdata).to_wire())
+        .collect::<Result<Vec<Vec<u8>>, DnsSerializeError>>()
+        .ok()?;
+    rdatas.sort();
+    rdatas.dedup();
+    for rdata in rdatas {
+        data.extend_from_slice(&owner.to_wire());
+        data.extend_from_slice(&u16::from(rrset[0].rr_type).to_be_bytes());
+        data.extend_from_slice(&u16::from(rrset[0].rr_class).to_be_bytes());
+        data.extend_from_slice(&original_ttl.to_be_bytes());
+        data.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        data.extend_from_slice(&rdata);
+    }
+
+    return Some(data);
+}
+
+fn verify_signature(algorithm: u8, public_key: &[u8], data: &[u8], signature: &[u8]) -> bool {
+    return match algorithm {
+        8 => {
+            // exponent length, exponent and modulus. see: https://www.rfc-editor.org/rfc/rfc3110#section-2
+            let (exponent_length, exponent_offset) = match public_key {
+                [0, high, low, ..] => (u16::from_be_bytes([*high, *low]) as usize, 3),
+                [length, ..] => (*length as usize, 1),
+                [] => return false,
+            };
+            if public_key.len() <= exponent_offset + exponent_length {
+                return false;
+            }
+
+            signature::RsaPublicKeyComponents {
+                n: &public_key[exponent_offset + exponent_length..],
+                e: &public_key[exponent_offset..exponent_offset + exponent_length],
+            }
+            .verify(
+                // zones still use 1024 bit keys
+                &signature::RSA_PKCS1_1024_8192_SHA256_FOR_LEGACY_USE_ONLY,
+                data,
+                signature,
+            )
+            .is_ok()
+        }
+        13 => {
+            // the point is written without the uncompressed form prefix.
+            // see: https://www.rfc-editor.org/rfc/rfc6605#section-4
+            let mut point = vec![0x04];
+            point.extend_from_slice(public_key);
+
+            signature::UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_FIXED, point)
+                .verify(data, signature)
+                .is_ok()
+        }
+        15 => signature::UnparsedPublicKey::new(&signature::ED25519, public_key)
+            .verify(data, signature)
+            .is_ok(),
+        _ => false,
+    };
+}
+
+// serial number arithmetic, so that the times survive the wrap in 2106.
+// see: https://www.rfc-editor.org/rfc/rfc4034#section-3.1.5
+fn is_in_validity_period(inception: u32, expiration: u32, now: u32) -> bool {
+    return now.wrapping_sub(inception) as i32 >= 0 && expiration.wrapping_sub(now) as i32 >= 0;
+}
+
+// returns the labels field of the first RRSIG that verifies the RRset with one of the keys.
+// see: https://www.rfc-editor.org/rfc/rfc4035#section-5.3.1
+fn verify_rrset(
+    rrset: &[&DnsResourceRecord],
+    rrsigs: &[&DnsResourceRecord],
+    keys: &ZoneKeys,
+    now: u32,
+) -> Option<u8> {
+    let owner = &rrset.first()?.name;
+
+    for rrsig in rrsigs {
+        let RData::RRSIG {
+            type_covered,
+            algorithm,
+            labels,
+            expiration,
+            inception,
+            key_tag,
+            signer_name,
+            signature,
+            ..
+        } = &rrsig.rdata
+        else {
+            continue;
+        };
+        if *type_covered != rrset[0].rr_type
+            || rrsig.rr_class != rrset[0].rr_class
+            || signer_name != &keys.zone
+            || !owner.is_subdomain_of(signer_name)
+            || *labels as usize > owner.label_count()
+            || !is_in_validity_period(*inception, *expiration, now)
+        {
+            continue;
+        }
+        let Some(data) = create_signed_data(rrset, &rrsig.rdata) else {
+            continue;
+        };
+
+        for dnskey in &keys.dnskeys {
+            let RData::DNSKEY {
+                flags,
+                protocol,
+                algorithm: key_algorithm,
+                public_key,
+            } = dnskey
+            else {
+                continue;
+            };
+            if flags & DNSKEY_FLAG_ZONE == 0
+                || flags & DNSKEY_FLAG_REVOKE != 0
+                || *protocol != DNSKEY_PROTOCOL
+                || key_algorithm != algorithm
+                || compute_key_tag(dnskey) != *key_tag
+            {
+                continue;
+            }
+
+            if verify_signature(*algorithm, public_key, &data, signature) {
+                return Some(*labels);
+            }
+        }
+    }
+
+    return None;
+}
+#[cfg(test)]
+mod verify_rrset {
+    use crate::dns::{
+        dnssec::{ZoneKeys, verify_rrset},
+        model::{DnsClass, DnsResourceRecord, DnsType, RData},
+    };
+
+    fn create_records() -> (DnsResourceRecord, DnsResourceRecord, ZoneKeys) {
+        // from rfc8080 6.1
+        let mx = DnsResourceRecord {
+            name: "example.com.".parse().unwrap(),
+            rr_type: DnsType::MX,
+            rr_class: DnsClass::IN,
+            ttl: 3600,
+            rdata: RData::MX {
+                preference: 10,
+                exchange: "mail.example.com.".parse().unwrap(),
+            },
+        };
+        let rrsig = DnsResourceRecord {
+            name: "example.com.".parse().unwrap(),
+            rr_type: DnsType::RRSIG,
+            rr_class: DnsClass::IN,
+            ttl: 3600,
+            rdata: RData::RRSIG {
+                type_covered: DnsType::MX,
+                algorithm: 15,
+                labels: 2,
+                original_ttl: 3600,
+                expiration: 1440021600,
+                inception: 1438207200,
+                key_tag: 3613,
+                signer_name: "example.com.".parse().unwrap(),
+                signature: vec![
+                    0xa0, 0xbf, 0x64, 0xac, 0x9b, 0xa7, 0xef, 0x17, 0xc1, 0x38, 0x85, 0x9c, 0x18,
+                    0x78, 0xbb, 0x99, 0xa8, 0x39, 0xfe, 0x17, 0x59, 0xac, 0xa5, 0xb0, 0xd7, 0x98,
+                    0xcf, 0x1a, 0xb1, 0xe9, 0x8d, 0x07, 0x91, 0x02, 0xf4, 0xdd, 0xb3, 0x36, 0x8f,
+                    0x0f, 0xe4, 0x0b, 0xb3, 0x77, 0xf1, 0xf0, 0x0e, 0x0c, 0xdd, 0xed, 0xb7, 0x99,
+                    0x16, 0x7d, 0x56, 0xb6, 0xe9, 0x32, 0x78, 0x30, 0x72, 0xba, 0x8d, 0x02,
+                ],
+            },
+        };
+        let keys = ZoneKeys {
+            zone: "example.com.".parse().unwrap(),
+            dnskeys: vec![RData::DNSKEY {
+                flags: 257,
+                protocol: 3,
+                algorithm: 15,
+                public_key: vec![
+                    0x97, 0x4d, 0x96, 0xa2, 0x2d, 0x22, 0x4b, 0xc0, 0x1a, 0xdb, 0x91, 0x50, 0x91,
+                    0x47, 0x7d, 0x44, 0xcc, 0xd9, 0x1c, 0x9a, 0x41, 0xa1, 0x14, 0x30, 0x01, 0x01,
+                    0x17, 0xd5, 0x2c, 0x59, 0x24, 0x0e,
+                ],
+            }],
+        };
+
+        return (mx, rrsig, keys);
+    }
+
+    #[test]
+    fn case1() {
+        let (mx, rrsig, keys) = create_records();
+
+        assert_eq!(verify_rrset(&[&mx], &[&rrsig], &keys, 1440000000), Some(2));
+    }
+
+    #[test]
+    fn case2() {
+        // owner names and names in rdata are compared in lowercase
+        let (mut mx, rrsig, keys) = create_records();
+        mx.name = "EXAMPLE.com.".parse().unwrap();
+        mx.rdata = RData::MX {
+            preference: 10,
+            exchange: "Mail.Example.com.".parse().unwrap(),
+        };
+
+        assert_eq!(verify_rrset(&[&mx], &[&rrsig], &keys, 1440000000), Some(2));
+    }
+
+    #[test]
+    fn case3() {
+        let (mut mx, rrsig, keys) = create_records();
+
+        // expired
+        assert_eq!(verify_rrset(&[&mx], &[&rrsig], &keys, 1440021601), None);
+
+        // tampered
+        mx.rdata = RData::MX {
+            preference: 20,
+            exchange: "mail.example.com.".parse().unwrap(),
+        };
+        assert_eq!(verify_rrset(&[&mx], &[&rrsig], &keys, 1440000000), None);
+    }
+}
+
+fn find_rrset<'a>(
+    records: &'a [DnsResourceRecord],
+    name: &DomainName,
+    rr_type: DnsType,
+) -> Vec<&'a DnsResourceRecord> {
+    return records
+        .iter()
+        .filter(|rr| rr.rr_type == rr_type && &rr.name == name)
+        .collect();
+}
+
+fn find_rrsigs<'a>(
+    records: &'a [DnsResourceRecord],
+    name: &DomainName,
+    rr_type: DnsType,
+) -> Vec<&'a DnsResourceRecord> {
+    return records
+        .iter()
+        .filter(|rr| match &rr.rdata {
+            RData::RRSIG { type_covered, .. } => *type_covered == rr_type && &rr.name == name,
+            _ => false,
+        })
+        .collect();
+}
+
+/// Verifies the DNSKEY RRset of the zone in a DNSKEY response with the DS RRset from the
+/// parent, or with [`create_root_trust_anchors`](super::create_root_trust_anchors) for the root. Err(Insecure) when none of the
+/// DS records can be used by this implementation.
+/// see: <https://www.rfc-editor.org/rfc/rfc4035#section-5.2>
+pub fn validate_dnskeys(
+    zone: &DomainName,
+    message: &DnsMessage,
+    ds_set: &[RData],
+    now: u32,
+) -> Result<ZoneKeys, ValidationStatus> {
+    let usable_ds_set: Vec<&RData> = ds_set
+        .iter()
+        .filter(|ds| match ds {
+            RData::DS {
+                algorithm,
+                digest_type,
+                ..
+            } => is_supported_algorithm(*algorithm) && matches!(digest_type, 1 | 2 | 4),
+            _ => false,
+        })
+        .collect();
+    if usable_ds_set.is_empty() {
+        return Err(ValidationStatus::Insecure);
+    }
+
+    let rrset = find_rrset(&message.answers, zone, DnsType::DNSKEY);
+    let rrsigs = find_rrsigs(&message.answers, zone, DnsType::DNSKEY);
+    let secure_entry_points: Vec<RData> = rrset
+        .iter()
+        .map(|rr| rr.rdata.clone())
+        .filter(|dnskey| {
+            usable_ds_set.iter().any(|ds| match (ds, dnskey) {
+                (
+                    RData::DS {
+                        key_tag,
+                        algorithm,
+                        digest_type,
+                        digest,
+                    },
+                    RData::DNSKEY {
+                        algorithm: key_algorithm,
+                        ..
+                    },
+                ) => {
+                    algorithm == key_algorithm
+                        && *key_tag == compute_key_tag(dnskey)
+                        && compute_ds_digest(zone, dnskey, *digest_type).as_ref() == Some(digest)
+                }
+                _ => false,
+            })
+        })
+        .collect();
+
+    let entry_keys = ZoneKeys {
+        zone: zone.clone(),
+        dnskeys: secure_entry_points,
+    };
+    if verify_rrset(&rrset, &rrsigs, &entry_keys, now).is_none() {
+        return Err(ValidationStatus::Bogus);
+    }
+
+    return Ok(ZoneKeys {
+        zone: zone.clone(),
+        dnskeys: rrset.iter().map(|rr| rr.rdata.clone()).collect(),
+    });
+}
+
+// verifies every RRset of the given types in the section. false if one of them is not signed
+fn verify_section(
+    records: &[DnsResourceRecord],
+    rr_types: &[DnsType],
+    keys: &ZoneKeys,
+    now: u32,
+) -> bool {
+    return records
+        .iter()
+        .filter(|rr| rr_types.contains(&rr.rr_type))
+        .all(|rr| {
+            let rrset = find_rrset(records, &rr.name, rr.rr_type);
+            let rrsigs = find_rrsigs(records, &rr.name, rr.rr_type);
+            verify_rrset(&rrset, &rrsigs, keys, now).is_some()
+        });
+}
+
+/// The child zone of a referral is secure when the parent signs its DS RRset, and insecure
+/// when the parent proves there is none. Ok holds the DS RRset.
+/// see: <https://www.rfc-editor.org/rfc/rfc4035#section-5.2>
+pub fn validate_referral(
+    keys: &ZoneKeys,
+    message: &DnsMessage,
+    child: &DomainName,
+    now: u32,
+) -> Result<Vec<RData>, ValidationStatus> {
+    let ds_rrset = find_rrset(&message.authorities, child, DnsType::DS);
+    if !ds_rrset.is_empty() {
+        let rrsigs = find_rrsigs(&message.authorities, child, DnsType::DS);
+        if verify_rrset(&ds_rrset, &rrsigs, keys, now).is_none() {
+            return Err(ValidationStatus::Bogus);
+        }
+
+        return Ok(ds_rrset.iter().map(|rr| rr.rdata.clone()).collect());
+    }
+
+    if !verify_section(
+        &message.authorities,
+        &[DnsType::NSEC, DnsType::NSEC3],
+        keys,
+        now,
+    ) {
+        return Err(ValidationStatus::Bogus);
+    }
+
+    let denials = Denials::new(keys, &message.authorities);
+    let proven = denials.nsecs.iter().any(|nsec| {
+        nsec.owner == *child
+            && nsec.types.contains(&DnsType::NS)
+            && !nsec.types.contains(&DnsType::DS)
+            && !nsec.types.contains(&DnsType::SOA)
+    }) || denials.find_matching_nsec3(child).is_some_and(|nsec3| {
+        nsec3.types.contains(&DnsType::NS)
+            && !nsec3.types.contains(&DnsType::DS)
+            && !nsec3.types.contains(&DnsType::SOA)
+    }) || denials
+        .find_closest_encloser(child)
+        .is_some_and(|(_, next_closer)| next_closer.opt_out);
+    if !proven {
+        return Err(ValidationStatus::Bogus);
+    }
+
+    return Err(ValidationStatus::Insecure);
+}
+
+struct Nsec {
+    owner: DomainName,
+    next: DomainName,
+    types: Vec<DnsType>,
+}
+impl Nsec {
+    // the zone's last NSEC points back to the apex
+    // see: https://www.rfc-editor.org/rfc/rfc4034#section-4.1.1
+    fn covers(&self, name: &DomainName) -> bool {
+        if self.owner < self.next {
+            return &self.owner < name && name < &self.next;
+        }
+
+        return &self.owner < name;
+    }
+}
+
+struct Nsec3 {
+    hash: Vec<u8>,
+    next_hash: Vec<u8>,
+    opt_out: bool,
+    types: Vec<DnsType>,
+    salt: Vec<u8>,
+    iterations: u16,
+}
+impl Nsec3 {
+    fn covers(&self, hash: &[u8]) -> bool {
+        if self.hash < self.next_hash {
+            return self.hash.as_slice() < hash && hash < self.next_hash.as_slice();
+        }
+
+        return self.hash.as_slice() < hash || hash < self.next_hash.as_slice();
+    }
+}
+
+// see: https://www.rfc-editor.org/rfc/rfc5155#section-5
+pub(super) fn compute_nsec3_hash(name: &DomainName, salt: &[u8], iterations: u16) -> Vec<u8> {
+    let mut data = name.to_lowercase().to_wire();
+    for _ in 0..=iterations {
+        data.extend_from_slice(salt);
+        data = digest::digest(&digest::SHA1_FOR_LEGACY_USE_ONLY, &data)
+            .as_ref()
+            .to_vec();
+    }
+
+    return data;
+}
+#[cfg(test)]
+mod compute_nsec3_hash {
+    use crate::dns::{dnssec::compute_nsec3_hash, encoding::print_base32hex};
+
+    #[test]
+    fn case1() {
+        // from rfc5155 appendix A
+        let salt = [0xaa, 0xbb, 0xcc, 0xdd];
+        assert_eq!(
+            print_base32hex(&compute_nsec3_hash(&"example.".parse().unwrap(), &salt, 12)),
+            "0P9MHAVEQVM6T7VBL5LOP2U3T2RP3TOM"
+        );
+        assert_eq!(
+            print_base32hex(&compute_nsec3_hash(
+                &"a.example.".parse().unwrap(),
+                &salt,
+                12
+            )),
+            "35MTHGPGCU1QG68FAB165KLNSNK3DPVL"
+        );
+    }
+}
+
+// the NSEC and NSEC3 records of a zone found in a response, with their signatures checked
+struct Denials {
+    nsecs: Vec<Nsec>,
+    nsec3s: Vec<Nsec3>,
+}
+impl Denials {
+    fn new(keys: &ZoneKeys, records: &[DnsResourceRecord]) -> Self {
+        let nsecs = records
+            .iter()
+            .filter(|rr| rr.name.is_subdomain_of(&keys.zone))
+            .filter_map(|rr| match &rr.rdata {
+                RData::NSEC {
+                    next_domain_name,
+                    types,
+                } => Some(Nsec {
+                    owner: rr.name.clone(),
+                    next: next_domain_name.clone(),
+                    types: types.clone(),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        // NSEC3 owners are the hash under the zone apex
+        let nsec3s = records
+            .iter()
+            .filter(|rr| rr.name.parent().as_ref() == Some(&keys.zone))
+            .filter_map(|rr| match &rr.rdata {
+                RData::NSEC3 {
+                    hash_algorithm: NSEC3_HASH_SHA1,
+                    flags,
+                    iterations,
+                    salt,
+                    next_hashed_owner_name,
+                    types,
+                } => Some(Nsec3 {
+                    hash: parse_base32hex(std::str::from_utf8(rr.name.labels().next()?).ok()?)
+                        .ok()?,
+                    next_hash: next_hashed_owner_name.clone(),
+                    opt_out: flags & NSEC3_FLAG_OPT_OUT != 0,
+                    types: types.clone(),
+                    salt: salt.clone(),
+                    iterations: *iterations,
+                }),
+                _ => None,
+            })
+            .collect();
+
+        return Denials { nsecs, nsec3s };
+    }
+
+    fn find_matching_nsec3(&self, name: &DomainName) -> Option<&Nsec3> {
+        return self
+            .nsec3s
+            .iter()
+            .find(|nsec3| nsec3.hash == compute_nsec3_hash(name, &nsec3.salt, nsec3.iterations));
+    }
+
+    fn find_covering_nsec3(&self, name: &DomainName) -> Option<&Nsec3> {
+        return self
+            .nsec3s
+            .iter()
+            .find(|nsec3| nsec3.covers(&compute_nsec3_hash(name, &nsec3.salt, nsec3.iterations)));
+    }
+
+    // the closest encloser and the NSEC3 that covers the next closer name.
+    // see: https://www.rfc-editor.org/rfc/rfc5155#section-8.3
+    fn find_closest_encloser(&self, name: &DomainName) -> Option<(DomainName, &Nsec3)> {
+        let mut next_closer = name.clone();
+        while let Some(closest_encloser) = next_closer.parent() {
+            if self.find_matching_nsec3(&closest_encloser).is_some() {
+                return self
+                    .find_covering_nsec3(&next_closer)
+                    .map(|nsec3| (closest_encloser, nsec3));
+            }
+            next_closer = closest_encloser;
+        }
+
+        return None;
+    }
+
+    // the longest name that is an ancestor of the name and exists, as told by an NSEC covering it
+    fn find_nsec_closest_encloser(&self, name: &DomainName, nsec: &Nsec) -> DomainName {
+        let mut closest_encloser = name.clone();
+        while let Some(parent) = closest_encloser.parent() {
+            closest_encloser = parent;
+            if nsec.owner.is_subdomain_of(&closest_encloser)
+                || nsec.next.is_subdomain_of(&closest_encloser)
+            {
+                break;
+            }
+        }
+
+        return closest_encloser;
+    }
+
+    // see: https://www.rfc-editor.org/rfc/rfc4035#section-5.4
+    fn prove_nxdomain(&self, name: &DomainName) -> bool {
+        if let Some(nsec) = self.nsecs.iter().find(|nsec| nsec.covers(name)) {
+            let closest_encloser = self.find_nsec_closest_encloser(name, nsec);
+            let wildcard = create_wildcard(&closest_encloser);
+            return wildcard
+                .is_some_and(|wildcard| self.nsecs.iter().any(|nsec| nsec.covers(&wildcard)));
+        }
+
+        if let Some((closest_encloser, _)) = self.find_closest_encloser(name) {
+            let wildcard = create_wildcard(&closest_encloser);
+            return wildcard.is_some_and(|wildcard| self.find_covering_nsec3(&wildcard).is_some());
+        }
+
+        return false;
+    }
+
+    fn prove_nodata(&self, name: &DomainName, q_type: DnsType) -> bool {
+        let lacks_type =
+            |types: &Vec<DnsType>| !types.contains(&q_type) && !types.contains(&DnsType::CNAME);
+
+        if self
+            .nsecs
+            .iter()
+            .any(|nsec| &nsec.owner == name && lacks_type(&nsec.types))
+        {
+            return true;
+        }
+
+        // a wildcard that matches the name but not the type
+        if let Some(nsec) = self.nsecs.iter().find(|nsec| nsec.covers(name)) {
+            let closest_encloser = self.find_nsec_closest_encloser(name, nsec);
+            if let Some(wildcard) = create_wildcard(&closest_encloser) {
+                return self
+                    .nsecs
+                    .iter()
+                    .any(|nsec| nsec.owner == wildcard && lacks_type(&nsec.types));
+            }
+        }
+
+        return self
+            .find_matching_nsec3(name)
+            .is_some_and(|nsec3| lacks_type(&nsec3.types));
+    }
+
+    // a wildcard answer is only valid when the name itself does not exist.
+    // see: https://www.rfc-editor.org/rfc/rfc4035#section-5.3.4
+    fn prove_wildcard_expansion(&self, name: &DomainName, labels: u8) -> bool {
+        if self.nsecs.iter().any(|nsec| nsec.covers(name)) {
+            return true;
+        }
+
+        // the next closer name is one label longer than the wildcard's parent
+        let name_labels: Vec<Vec<u8>> = name.labels().map(|label| label.to_vec()).collect();
+        let next_closer = DomainName::from_labels(
+            name_labels[name_labels.len() - labels as usize - 1..].to_vec(),
+        );
+        return next_closer
+            .is_ok_and(|next_closer| self.find_covering_nsec3(&next_closer).is_some());
+    }
+}
+
+fn create_wildcard(name: &DomainName) -> Option<DomainName> {
+    let mut labels = vec![b"*".to_vec()];
+    labels.extend(name.labels().map(|label| label.to_vec()));
+
+    return DomainName::from_labels(labels).ok();
+}
+
+/// The zone below `zone` that signed records about `name` in the message, as when the servers
+/// of the root also serve arpa. The deepest one is taken. Its keys have to be fetched to
+/// validate the message.
+pub fn find_descendant_signer(
+    message: &DnsMessage,
+    zone: &DomainName,
+    name: &DomainName,
+) -> Option<DomainName> {
+    return message
+        .answers
+        .iter()
+        .chain(message.authorities.iter())
+        .filter_map(|rr| match &rr.rdata {
+            RData::RRSIG { signer_name, .. } => Some(signer_name),
+            _ => None,
+        })
+        .filter(|signer_name| {
+            *signer_name != zone
+                && signer_name.is_subdomain_of(zone)
+                && name.is_subdomain_of(signer_name)
+        })
+        .max_by_key(|signer_name| signer_name.label_count())
+        .cloned();
+}
+#[cfg(test)]
+mod find_descendant_signer {
+    use crate::dns::{
+        dnssec::find_descendant_signer,
+        fixtures::{Zone, create_message, create_rr},
+        model::{DnsHeaderRcode, DnsType, RData},
+    };
+
+    #[test]
+    fn case1() {
+        let root = Zone::new(".", 1);
+        let arpa = Zone::new("arpa.", 2);
+        let in_addr_arpa = Zone::new("in-addr.arpa.", 3);
+        let mut authorities = vec![create_rr(
+            "in-addr.arpa.",
+            DnsType::NS,
+            RData::NS("ns.in-addr.arpa.".parse().unwrap()),
+        )];
+        authorities.extend(arpa.sign(
+            vec![create_rr("in-addr.arpa.", DnsType::DS, in_addr_arpa.ds())],
+            2,
+        ));
+        let message = create_message(DnsHeaderRcode::NoError, vec![], authorities, vec![]);
+        let name = "1.2.0.192.in-addr.arpa.".parse().unwrap();
+
+        assert_eq!(
+            find_descendant_signer(&message, &root.name, &name),
+            Some(arpa.name.clone())
+        );
+        // signed by the zone itself
+        assert_eq!(find_descendant_signer(&message, &arpa.name, &name), None);
+        // not a zone the name is in
+        assert_eq!(
+            find_descendant_signer(&message, &root.name, &"example.com.".parse().unwrap()),
+            None
+        );
+    }
+}
+
+/// Labels the answer to a question sent to a server of the zone, whose keys come from
+/// [`validate_dnskeys`]. `now` is in seconds since the epoch.
+/// see: <https://www.rfc-editor.org/rfc/rfc4035#section-5.3>
+pub fn validate_response(
+    keys: &ZoneKeys,
+    message: &DnsMessage,
+    q_name: &DomainName,
+    q_type: DnsType,
+    now: u32,
+) -> ValidationStatus {
+    if !message.answers.is_empty() {
+        let mut status = ValidationStatus::Secure;
+        for rr in message
+            .answers
+            .iter()
+            .filter(|rr| rr.rr_type != DnsType::RRSIG)
+        {
+            // other zones have to be validated with their own keys
+            if !rr.name.is_subdomain_of(&keys.zone) {
+                status = status.combine(ValidationStatus::Indeterminate);
+                continue;
+            }
+
+            let rrset = find_rrset(&message.answers, &rr.name, rr.rr_type);
+            let rrsigs = find_rrsigs(&message.answers, &rr.name, rr.rr_type);
+            let Some(labels) = verify_rrset(&rrset, &rrsigs, keys, now) else {
+                return ValidationStatus::Bogus;
+            };
+
+            if (labels as usize) < rr.name.label_count() {
+                if !verify_section(
+                    &message.authorities,
+                    &[DnsType::NSEC, DnsType::NSEC3],
+                    keys,
+                    now,
+                ) {
+                    return ValidationStatus::Bogus;
+                }
+                let denials = Denials::new(keys, &message.authorities);
+                if !denials.prove_wildcard_expansion(&rr.name, labels) {
+                    return ValidationStatus::Bogus;
+                }
+            }
+        }
+
+        return status;
+    }
+
+    if !verify_section(
+        &message.authorities,
+        &[DnsType::SOA, DnsType::NSEC, DnsType::NSEC3],
+        keys,
+        now,
+    ) {
+        return ValidationStatus::Bogus;
+    }
+
+    let denials = Denials::new(keys, &message.authorities);
+    let proven = if message.rcode() == DnsHeaderRcode::NoNameError {
+        denials.prove_nxdomain(q_name)
+    } else {
+        denials.prove_nodata(q_name, q_type)
+    };
+    if !proven {
+        return ValidationStatus::Bogus;
+    }
+
+    return ValidationStatus::Secure;
+}
+#[cfg(test)]
+mod validate {
+    use crate::dns::{
+        dnssec::{
+            ValidationStatus, compute_key_tag, compute_nsec3_hash, create_signed_data,
+            validate_dnskeys, validate_referral, validate_response, verify_signature,
+        },
+        encoding::parse_hex,
+        fixtures::{NOW, Zone, create_message, create_rr},
+        model::{DnsHeaderRcode, DnsType, RData},
+        name::DomainName,
+    };
+
+    #[test]
+    fn dnskeys() {
+        let root = Zone::new(".", 1);
+        let message = create_message(DnsHeaderRcode::NoError, root.sign_dnskeys(), vec![], vec![]);
+
+        assert_eq!(
+            validate_dnskeys(&root.name, &message, &[root.ds()], NOW),
+            Ok(root.keys())
+        );
+
+        // the DS of another key
+        assert_eq!(
+            validate_dnskeys(&root.name, &message, &[Zone::new(".", 2).ds()], NOW),
+            Err(ValidationStatus::Bogus)
+        );
+
+        // RSASHA1 is not supported
+        let ds = RData::DS {
+            key_tag: 1,
+            algorithm: 5,
+            digest_type: 2,
+            digest: vec![0; 32],
+        };
+        assert_eq!(
+            validate_dnskeys(&root.name, &message, &[ds], NOW),
+            Err(ValidationStatus::Insecure)
+        );
+    }
+
+    #[test]
+    fn secure_referral() {
+        let root = Zone::new(".", 1);
+        let com = Zone::new("com.", 2);
+        let mut authorities = vec![create_rr(
+            "com.",
+            DnsType::NS,
+            RData::NS("a.gtld-servers.net.".parse().unwrap()),
+        )];
+        authorities.extend(root.sign(vec![create_rr("com.", DnsType::DS, com.ds())], 1));
+        let message = create_message(DnsHeaderRcode::NoError, vec![], authorities, vec![]);
+
+        assert_eq!(
+            validate_referral(&root.keys(), &message, &com.name, NOW),
+            Ok(vec![com.ds()])
+        );
+
+        let dnskey_message =
+            create_message(DnsHeaderRcode::NoError, com.sign_dnskeys(), vec![], vec![]);
+        assert_eq!(
+            validate_dnskeys(&com.name, &dnskey_message, &[com.ds()], NOW),
+            Ok(com.keys())
+        );
+    }
+
+    #[test]
+    fn insecure_referral() {
+        let com = Zone::new("com.", 2);
+        let nsec = create_rr(
+            "example.com.",
+            DnsType::NSEC,
+            RData::NSEC {
+                next_domain_name: "example1.com.".parse().unwrap(),
+                types: vec![DnsType::NS, DnsType::RRSIG, DnsType::NSEC],
+            },
+        );
+        let child = "example.com.".parse().unwrap();
+
+        let message = create_message(
+            DnsHeaderRcode::NoError,
+            vec![],
+            com.sign(vec![nsec.clone()], 2),
+            vec![],
+        );
+        assert_eq!(
+            validate_referral(&com.keys(), &message, &child, NOW),
+            Err(ValidationStatus::Insecure)
+        );
+
+        // the proof must be signed
+        let message = create_message(DnsHeaderRcode::NoError, vec![], vec![nsec], vec![]);
+        assert_eq!(
+            validate_referral(&com.keys(), &message, &child, NOW),
+            Err(ValidationStatus::Bogus)
+        );
+
+        // no proof at all
+        let message = create_message(DnsHeaderRcode::NoError, vec![], vec![], vec![]);
+        assert_eq!(
+            validate_referral(&com.keys(), &message, &child, NOW),
+            Err(ValidationStatus::Bogus)
+        );
+    }
+
+    #[test]
+    fn nsec3_insecure_referral() {
+        let com = Zone::new("com.", 2);
+        let child: DomainName = "example.com.".parse().unwrap();
+        let referral = |types: Vec<DnsType>| {
+            let nsec3 = com.create_nsec3("example.com.", "n0.com.", 0, types);
+            return create_message(
+                DnsHeaderRcode::NoError,
+                vec![],
+                com.sign(vec![nsec3], 2),
+                vec![],
+            );
+        };
+
+        assert_eq!(
+            validate_referral(&com.keys(), &referral(vec![DnsType::NS]), &child, NOW),
+            Err(ValidationStatus::Insecure)
+        );
+        // the apex of a zone, not a delegation
+        assert_eq!(
+            validate_referral(
+                &com.keys(),
+                &referral(vec![DnsType::NS, DnsType::SOA]),
+                &child,
+                NOW
+            ),
+            Err(ValidationStatus::Bogus)
+        );
+        // no delegation at all
+        assert_eq!(
+            validate_referral(&com.keys(), &referral(vec![DnsType::A]), &child, NOW),
+            Err(ValidationStatus::Bogus)
+        );
+    }
+
+    #[test]
+    fn opt_out_referral() {
+        let com = Zone::new("com.", 2);
+        let child: DomainName = "example.com.".parse().unwrap();
+        // find names whose hashes surround the child's, so that an NSEC3 covers it
+        let hash = |name: &DomainName| compute_nsec3_hash(name, &[0xab], 1);
+        let mut names: Vec<String> = (0..64).map(|index| format!("n{}.com.", index)).collect();
+        names.sort_by_key(|name| hash(&name.parse().unwrap()));
+        let child_hash = hash(&child);
+        let previous = names
+            .iter()
+            .rev()
+            .find(|name| hash(&name.parse().unwrap()) < child_hash)
+            .unwrap();
+        let next = names
+            .iter()
+            .find(|name| hash(&name.parse().unwrap()) > child_hash)
+            .unwrap();
+
+        let mut authorities = com.sign(
+            vec![com.create_nsec3("com.", "n0.com.", 0, vec![DnsType::NS, DnsType::SOA])],
+            1,
+        );
+        authorities.extend(com.sign(
+            vec![com.create_nsec3(previous, next, 1, vec![DnsType::NS])],
+            2,
+        ));
+        let message = create_message(DnsHeaderRcode::NoError, vec![], authorities, vec![]);
+
+        assert_eq!(
+            validate_referral(&com.keys(), &message, &child, NOW),
+            Err(ValidationStatus::Insecure)
+        );
+    }
+
+    #[test]
+    fn positive_response() {
+        let zone = Zone::new("example.com.", 3);
+        let a = create_rr(
+            "www.example.com.",
+            DnsType::A,
+            RData::A("192.0.2.1".parse().unwrap()),
+        );
+        let q_name: DomainName = "www.example.com.".parse().unwrap();
+
+        let message = create_message(
+            DnsHeaderRcode::NoError,
+            zone.sign(vec![a.clone()], 3),
+            vec![],
+            vec![],
+        );
+        assert_eq!(
+            validate_response(&zone.keys(), &message, &q_name, DnsType::A, NOW),
+            ValidationStatus::Secure
+        );
+
+        let mut answers = zone.sign(vec![a.clone()], 3);
+        answers[0].rdata = RData::A("192.0.2.2".parse().unwrap());
+        let message = create_message(DnsHeaderRcode::NoError, answers, vec![], vec![]);
+        assert_eq!(
+            validate_response(&zone.keys(), &message, &q_name, DnsType::A, NOW),
+            ValidationStatus::Bogus
+        );
+
+        // signed by a key that is not in the chain of trust
+        let message = create_message(
+            DnsHeaderRcode::NoError,
+            Zone::new("example.com.", 4).sign(vec![a], 3),
+            vec![],
+            vec![],
+        );
+        assert_eq!(
+            validate_response(&zone.keys(), &message, &q_name, DnsType::A, NOW),
+            ValidationStatus::Bogus
+        );
+    }
+
+    #[test]
+    fn wildcard_response() {
+        let zone = Zone::new("example.com.", 3);
+        let a = create_rr(
+            "foo.example.com.",
+            DnsType::A,
+            RData::A("192.0.2.1".parse().unwrap()),
+        );
+        let nsec = create_rr(
+            "example.com.",
+            DnsType::NSEC,
+            RData::NSEC {
+                next_domain_name: "www.example.com.".parse().unwrap(),
+                types: vec![DnsType::NS, DnsType::SOA, DnsType::RRSIG, DnsType::NSEC],
+            },
+        );
+        let q_name: DomainName = "foo.example.com.".parse().unwrap();
+
+        let message = create_message(
+            DnsHeaderRcode::NoError,
+            zone.sign(vec![a.clone()], 2),
+            zone.sign(vec![nsec], 2),
+            vec![],
+        );
+        assert_eq!(
+            validate_response(&zone.keys(), &message, &q_name, DnsType::A, NOW),
+            ValidationStatus::Secure
+        );
+
+        // foo.example.com. may exist
+        let message = create_message(
+            DnsHeaderRcode::NoError,
+            zone.sign(vec![a], 2),
+            vec![],
+            vec![],
+        );
+        assert_eq!(
+            validate_response(&zone.keys(), &message, &q_name, DnsType::A, NOW),
+            ValidationStatus::Bogus
+        );
+    }
+
+    #[test]
+    fn nxdomain_response() {
+        let zone = Zone::new("example.com.", 3);
+        let soa = create_rr(
+            "example.com.",
+            DnsType::SOA,
+            RData::SOA {
+                mname: "ns.example.com.".parse().unwrap(),
+                rname: "admin.example.com.".parse().unwrap(),
+                serial: 1,
+                refresh: 3600,
+                retry: 600,
+                expire: 86400,
+                minimum: 3600,
+            },
+        );
+        let create_nsec = |owner: &str, next: &str| {
+            create_rr(
+                owner,
+                DnsType::NSEC,
+                RData::NSEC {
+                    next_domain_name: next.parse().unwrap(),
+                    types: vec![DnsType::A, DnsType::RRSIG, DnsType::NSEC],
+                },
+            )
+        };
+        let q_name: DomainName = "nope.example.com.".parse().unwrap();
+
+        // covers both nope.example.com. and *.example.com.
+        let mut authorities = zone.sign(vec![soa.clone()], 2);
+        authorities.extend(zone.sign(vec![create_nsec("example.com.", "www.example.com.")], 2));
+        let message = create_message(DnsHeaderRcode::NoNameError, vec![], authorities, vec![]);
+        assert_eq!(
+            validate_response(&zone.keys(), &message, &q_name, DnsType::A, NOW),
+            ValidationStatus::Secure
+        );
+
+        // *.example.com. may exist
+        let mut authorities = zone.sign(vec![soa], 2);
+        authorities.extend(zone.sign(vec![create_nsec("a.example.com.", "www.example.com.")], 3));
+        let message = create_message(DnsHeaderRcode::NoNameError, vec![], authorities, vec![]);
+        assert_eq!(
+            validate_response(&zone.keys(), &message, &q_name, DnsType::A, NOW),
+            ValidationStatus::Bogus
+        );
+    }
+
+    #[test]
+    fn nodata_response() {
+        let zone = Zone::new("example.com.", 3);
+        let nsec3 = zone.create_nsec3(
+            "www.example.com.",
+            "example.com.",
+            0,
+            vec![DnsType::A, DnsType::RRSIG],
+        );
+        let q_name: DomainName = "www.example.com.".parse().unwrap();
+        let message = create_message(
+            DnsHeaderRcode::NoError,
+            vec![],
+            zone.sign(vec![nsec3], 3),
+            vec![],
+        );
+
+        assert_eq!(
+            validate_response(&zone.keys(), &message, &q_name, DnsType::AAAA, NOW),
+            ValidationStatus::Secure
+        );
+        assert_eq!(
+            validate_response(&zone.keys(), &message, &q_name, DnsType::A, NOW),
+            ValidationStatus::Bogus
+        );
+    }
+
+    #[test]
+    fn rsa_signature() {
+        // signed with a throwaway 1024 bit key
+        let public_key = parse_hex(concat!(
+            "03010001d6d6ab14d246bb4a250b3a51c4a5629c504c4362ad33b9f9d1ddd6a82fa4e488b22cf9ff16f4e5",
+            "bb6317cde7d2ba58a3a5938ee6311200448929af3d58cbab713f9a81892cc0906492a5bace50427add6f36",
+            "8e5b64254d65f0cca1ad6337247e3b806d784e6037523c16ac77e31c9da7ab5476097fe7703f138dd6739e",
+            "349ebb",
+        ))
+        .unwrap();
+        let signature = parse_hex(concat!(
+            "416a8a4c17df9abed279db51b3ca828b8dbdf8143e84b8fc8dc9a491371caec3b752c9213d1e2469a6244b",
+            "d55a082cf31ece4350ad178990b53d9d8bc690ad262217b4851db0acbd7e57e284ba11cde1fef29040ec68",
+            "9ef1ae31bfbd9fe30fd25384242de4c2d7ba81fc763b63df1af65e01932adc42575ca9e22fc0d51a0744",
+        ))
+        .unwrap();
+        let rrsig = RData::RRSIG {
+            type_covered: DnsType::A,
+            algorithm: 8,
+            labels: 3,
+            original_ttl: 3600,
+            expiration: 1893456000,
+            inception: 1577836800,
+            key_tag: 11521,
+            signer_name: "example.com.".parse().unwrap(),
+            signature: vec![],
+        };
+        let a = create_rr(
+            "www.example.com.",
+            DnsType::A,
+            RData::A("192.0.2.1".parse().unwrap()),
+        );
+        let dnskey = RData::DNSKEY {
+            flags: 256,
+            protocol: 3,
+            algorithm: 8,
+            public_key: public_key.clone(),
+        };
+
+        let data = create_signed_data(&[&a], &rrsig).unwrap();
+        assert_eq!(compute_key_tag(&dnskey), 11521);
+        assert!(verify_signature(8, &public_key, &data, &signature));
+        assert!(!verify_signature(8, &public_key, &data[1..], &signature));
+    }
+}
@@ -0,0 +1,299 @@
+#![allow(clippy::needless_return)]
+
+// text encodings used by the presentation format of DNSSEC records
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE32HEX_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHIJKLMNOPQRSTUV";
+
+pub fn print_hex(data: &[u8]) -> String {
+    return data
+        .iter()
+        .map(|x| format!("{:02X}", x))
+        .collect::<Vec<String>>()
+        .join("");
+}
+
+pub fn parse_hex(string: &str) -> Result<Vec<u8>, String> {
+    if !string.is_ascii() || !string.len().is_multiple_of(2) {
+        return Err(format!("bad hex: {}", string));
+    }
+
+    return (0..string.len())
+        .step_by(2)
+        .map(|index| {
+            u8::from_str_radix(&string[index..index + 2], 16)
+                .map_err(|_| format!("bad hex: {}", string))
+        })
+        .collect();
+}
+#[cfg(test)]
+mod parse_hex {
+    use crate::dns::encoding::{parse_hex, print_hex};
+
+    #[test]
+    fn case1() {
+        assert_eq!(parse_hex("00ffAb").unwrap(), vec![0x00, 0xff, 0xab]);
+        assert_eq!(print_hex(&parse_hex("00ffab").unwrap()), "00FFAB");
+        assert!(parse_hex("abc").is_err());
+        assert!(parse_hex("zz").is_err());
+    }
+}
+
+// see: https://www.rfc-editor.org/rfc/rfc4648#section-4
+pub fn print_base64(data: &[u8]) -> String {
+    let mut string = String::new();
+    for chunk in data.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0_u32, |bits, (index, &x)| {
+            bits | (x as u32) << (16 - 8 * index)
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                string
+                    .push(BASE64_ALPHABET[(bits >> (18 - 6 * index) & 0b111111) as usize] as char);
+            } else {
+                string.push('=');
+            }
+        }
+    }
+
+    return string;
+}
+#[cfg(test)]
+mod print_base64 {
+    use crate::dns::encoding::print_base64;
+
+    #[test]
+    fn case1() {
+        // from rfc4648 10
+        assert_eq!(print_base64(b""), "");
+        assert_eq!(print_base64(b"f"), "Zg==");
+        assert_eq!(print_base64(b"fo"), "Zm8=");
+        assert_eq!(print_base64(b"foo"), "Zm9v");
+        assert_eq!(print_base64(b"foob"), "Zm9vYg==");
+        assert_eq!(print_base64(b"fooba"), "Zm9vYmE=");
+        assert_eq!(print_base64(b"foobar"), "Zm9vYmFy");
+    }
+}
+
+// padding is optional
+pub fn parse_base64(string: &str) -> Result<Vec<u8>, String> {
+    let data = string.trim_end_matches('=');
+    if string.len() - data.len() > 2 {
+        return Err(format!("bad base64: {}", string));
+    }
+
+    let mut bytes = Vec::new();
+    let mut bits = 0_u32;
+    let mut bit_count = 0;
+    for x in data.bytes() {
+        let value = BASE64_ALPHABET
+            .iter()
+            .position(|&y| y == x)
+            .ok_or(format!("bad base64: {}", string))?;
+        bits = bits << 6 | value as u32;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((bits >> bit_count) as u8);
+        }
+    }
+    if bit_count >= 6 {
+        return Err(format!("bad base64: {}", string));
+    }
+
+    return Ok(bytes);
+}
+#[cfg(test)]
+mod parse_base64 {
+    use crate::dns::encoding::parse_base64;
+
+    #[test]
+    fn case1() {
+        assert_eq!(parse_base64("").unwrap(), b"");
+        assert_eq!(parse_base64("Zg==").unwrap(), b"f");
+        assert_eq!(parse_base64("Zm8=").unwrap(), b"fo");
+        assert_eq!(parse_base64("Zm9vYmFy").unwrap(), b"foobar");
+        assert_eq!(parse_base64("Zm9vYg").unwrap(), b"foob");
+    }
+
+    #[test]
+    fn case2() {
+        assert!(parse_base64("Z").is_err());
+        assert!(parse_base64("Zm9v!").is_err());
+        assert!(parse_base64("Zg===").is_err());
+    }
+}
+
+// without padding, as NSEC3 hashes are written. see: https://www.rfc-editor.org/rfc/rfc5155#section-3.3
+pub fn print_base32hex(data: &[u8]) -> String {
+    let mut string = String::new();
+    for chunk in data.chunks(5) {
+        let bits = chunk.iter().enumerate().fold(0_u64, |bits, (index, &x)| {
+            bits | (x as u64) << (32 - 8 * index)
+        });
+        // every started 5 bit group is written
+        let digits = (chunk.len() * 8).div_ceil(5);
+        for index in 0..digits {
+            string.push(BASE32HEX_ALPHABET[(bits >> (35 - 5 * index) & 0b11111) as usize] as char);
+        }
+    }
+
+    return string;
+}
+#[cfg(test)]
+mod print_base32hex {
+    use crate::dns::encoding::print_base32hex;
+
+    #[test]
+    fn case1() {
+        // from rfc4648 10, without padding
+        assert_eq!(print_base32hex(b""), "");
+        assert_eq!(print_base32hex(b"f"), "CO");
+        assert_eq!(print_base32hex(b"fo"), "CPNG");
+        assert_eq!(print_base32hex(b"foo"), "CPNMU");
+        assert_eq!(print_base32hex(b"foob"), "CPNMUOG");
+        assert_eq!(print_base32hex(b"fooba"), "CPNMUOJ1");
+        assert_eq!(print_base32hex(b"foobar"), "CPNMUOJ1E8");
+    }
+}
+
+// case-insensitive, padding is not accepted
+pub fn parse_base32hex(string: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let mut bits = 0_u32;
+    let mut bit_count = 0;
+    for x in string.bytes() {
+        let value = BASE32HEX_ALPHABET
+            .iter()
+            .position(|&y| y == x.to_ascii_uppercase())
+            .ok_or(format!("bad base32hex: {}", string))?;
+        bits = bits << 5 | value as u32;
+        bit_count += 5;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((bits >> bit_count) as u8);
+        }
+    }
+    // the remaining bits are only padding and must be zero
+    if bit_count >= 5 || bits & ((1 << bit_count) - 1) != 0 {
+        return Err(format!("bad base32hex: {}", string));
+    }
+
+    return Ok(bytes);
+}
+#[cfg(test)]
+mod parse_base32hex {
+    use crate::dns::encoding::parse_base32hex;
+
+    #[test]
+    fn case1() {
+        assert_eq!(parse_base32hex("").unwrap(), b"");
+        assert_eq!(parse_base32hex("CO").unwrap(), b"f");
+        assert_eq!(parse_base32hex("cpng").unwrap(), b"fo");
+        assert_eq!(parse_base32hex("CPNMUOJ1E8").unwrap(), b"foobar");
+    }
+
+    #[test]
+    fn case2() {
+        assert!(parse_base32hex("CP").is_err());
+        assert!(parse_base32hex("C").is_err());
+        assert!(parse_base32hex("CW").is_err());
+        assert!(parse_base32hex("CO==").is_err());
+    }
+}
+
+// YYYYMMDDHHmmSS in UTC. see: https://www.rfc-editor.org/rfc/rfc4034#section-3.2
+pub fn print_dnssec_time(time: u32) -> String {
+    let days = (time / 86400) as i64;
+    let seconds = time % 86400;
+
+    // days to civil date. see: https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    return format!(
+        "{:04}{:02}{:02}{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+}
+#[cfg(test)]
+mod print_dnssec_time {
+    use crate::dns::encoding::print_dnssec_time;
+
+    #[test]
+    fn case1() {
+        assert_eq!(print_dnssec_time(0), "19700101000000");
+        assert_eq!(print_dnssec_time(951782400), "20000229000000");
+        assert_eq!(print_dnssec_time(1735689599), "20241231235959");
+        assert_eq!(print_dnssec_time(u32::MAX), "21060207062815");
+    }
+}
+
+// YYYYMMDDHHmmSS in UTC, or the seconds since the epoch as is.
+// see: https://www.rfc-editor.org/rfc/rfc4034#section-3.2
+pub fn parse_dnssec_time(string: &str) -> Result<u32, String> {
+    if string.len() != 14 {
+        return string
+            .parse::<u32>()
+            .map_err(|_| format!("bad time: {}", string));
+    }
+    if !string.bytes().all(|x| x.is_ascii_digit()) {
+        return Err(format!("bad time: {}", string));
+    }
+
+    let field = |range: std::ops::Range<usize>| string[range].parse::<i64>().unwrap();
+    let (year, month, day) = (field(0..4), field(4..6), field(6..8));
+    let (hour, minute, second) = (field(8..10), field(10..12), field(12..14));
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return Err(format!("bad time: {}", string));
+    }
+
+    // civil date to days. see: https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    // times after 2106 wrap around
+    return Ok((days * 86400 + hour * 3600 + minute * 60 + second) as u32);
+}
+#[cfg(test)]
+mod parse_dnssec_time {
+    use crate::dns::encoding::parse_dnssec_time;
+
+    #[test]
+    fn case1() {
+        assert_eq!(parse_dnssec_time("19700101000000").unwrap(), 0);
+        assert_eq!(parse_dnssec_time("20000229000000").unwrap(), 951782400);
+        assert_eq!(parse_dnssec_time("20030322173103").unwrap(), 1048354263);
+        assert_eq!(parse_dnssec_time("1048354263").unwrap(), 1048354263);
+    }
+
+    #[test]
+    fn case2() {
+        assert!(parse_dnssec_time("20031322173103").is_err());
+        assert!(parse_dnssec_time("2003032217310x").is_err());
+        assert!(parse_dnssec_time("-1").is_err());
+    }
+}
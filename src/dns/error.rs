@@ -0,0 +1,65 @@
+use core::fmt;
+
+// every variant carries the offset in the message where parsing failed
+#[derive(PartialEq, Debug, Clone)]
+pub enum DnsParseError {
+    Truncated(usize),
+    BadLabel(usize),
+    PointerLoop(usize),
+    BadPointer(usize),
+    NameTooLong(usize),
+    CountMismatch(usize),
+    BadRdata(usize),
+    BadOpt(usize),
+}
+impl fmt::Display for DnsParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DnsParseError::Truncated(offset) => write!(f, "truncated message at {}", offset),
+            DnsParseError::BadLabel(offset) => write!(f, "bad label at {}", offset),
+            DnsParseError::PointerLoop(offset) => {
+                write!(f, "compression pointer loop at {}", offset)
+            }
+            DnsParseError::BadPointer(offset) => {
+                write!(f, "bad compression pointer at {}", offset)
+            }
+            DnsParseError::NameTooLong(offset) => write!(f, "too long name at {}", offset),
+            DnsParseError::CountMismatch(offset) => {
+                write!(f, "section count mismatch at {}", offset)
+            }
+            DnsParseError::BadRdata(offset) => write!(f, "bad rdata at {}", offset),
+            DnsParseError::BadOpt(offset) => write!(f, "bad OPT record at {}", offset),
+        }
+    }
+}
+impl std::error::Error for DnsParseError {}
+
+// a field too long for the length written before it, with the name of the field and its length
+#[derive(PartialEq, Debug, Clone)]
+pub enum DnsSerializeError {
+    TooLong(&'static str, usize),
+}
+impl fmt::Display for DnsSerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DnsSerializeError::TooLong(field, length) => {
+                write!(f, "too long {}: {} bytes", field, length)
+            }
+        }
+    }
+}
+impl std::error::Error for DnsSerializeError {}
+
+// a problem in a zone master file, with where it was found
+#[derive(PartialEq, Debug, Clone)]
+pub struct ZoneParseError {
+    pub file: String,
+    pub line: usize,
+    pub message: String,
+}
+impl fmt::Display for ZoneParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.file, self.line, self.message)
+    }
+}
+impl std::error::Error for ZoneParseError {}
@@ -0,0 +1,196 @@
+#![allow(clippy::needless_return)]
+
+// records, replies and signed zones shared by the tests of the dns and resolver modules
+
+use ring::{
+    rand::SystemRandom,
+    signature::{ECDSA_P256_SHA256_FIXED_SIGNING, EcdsaKeyPair, Ed25519KeyPair, KeyPair},
+};
+
+use super::{
+    dnssec::{
+        ZoneKeys, compute_ds_digest, compute_key_tag, compute_nsec3_hash, create_signed_data,
+    },
+    encoding::print_base32hex,
+    model::{
+        DnsClass, DnsHeaderOpecode, DnsHeaderRcode, DnsHeaderSection, DnsMessage,
+        DnsQuestionSectionEntry, DnsResourceRecord, DnsType, RData,
+    },
+    name::DomainName,
+};
+
+pub fn create_rr(name: &str, rr_type: DnsType, rdata: RData) -> DnsResourceRecord {
+    return DnsResourceRecord {
+        name: name.parse().unwrap(),
+        rr_type,
+        rr_class: DnsClass::IN,
+        ttl: 3600,
+        rdata,
+    };
+}
+
+pub fn create_message(
+    rcode: DnsHeaderRcode,
+    answers: Vec<DnsResourceRecord>,
+    authorities: Vec<DnsResourceRecord>,
+    additionals: Vec<DnsResourceRecord>,
+) -> DnsMessage {
+    return DnsMessage {
+        header: DnsHeaderSection {
+            id: 0x1234,
+            qr: true,
+            opcode: DnsHeaderOpecode::Query,
+            aa: true,
+            tc: false,
+            rd: true,
+            ra: false,
+            z: false,
+            ad: true,
+            cd: false,
+            rcode,
+            qdcount: 1,
+            ancount: answers.len() as u16,
+            nscount: authorities.len() as u16,
+            arcount: additionals.len() as u16,
+        },
+        questions: vec![DnsQuestionSectionEntry {
+            q_name: "example.com.".parse().unwrap(),
+            q_type: DnsType::A,
+            q_class: DnsClass::IN,
+        }],
+        answers,
+        authorities,
+        additionals,
+        edns: None,
+    };
+}
+
+pub const NOW: u32 = 1700000000;
+
+// a locally signed zone, with an Ed25519 KSK and an ECDSA P-256 ZSK
+pub struct Zone {
+    pub name: DomainName,
+    ksk: Ed25519KeyPair,
+    zsk: EcdsaKeyPair,
+}
+impl Zone {
+    pub fn new(name: &str, seed: u8) -> Self {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+
+        return Zone {
+            name: name.parse().unwrap(),
+            ksk: Ed25519KeyPair::from_seed_unchecked(&[seed; 32]).unwrap(),
+            zsk: EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng)
+                .unwrap(),
+        };
+    }
+
+    pub fn ksk_dnskey(&self) -> RData {
+        return RData::DNSKEY {
+            flags: 257,
+            protocol: 3,
+            algorithm: 15,
+            public_key: self.ksk.public_key().as_ref().to_vec(),
+        };
+    }
+
+    pub fn zsk_dnskey(&self) -> RData {
+        return RData::DNSKEY {
+            flags: 256,
+            protocol: 3,
+            algorithm: 13,
+            // without the uncompressed form prefix
+            public_key: self.zsk.public_key().as_ref()[1..].to_vec(),
+        };
+    }
+
+    pub fn ds(&self) -> RData {
+        let dnskey = self.ksk_dnskey();
+        return RData::DS {
+            key_tag: compute_key_tag(&dnskey),
+            algorithm: 15,
+            digest_type: 2,
+            digest: compute_ds_digest(&self.name, &dnskey, 2).unwrap(),
+        };
+    }
+
+    pub fn keys(&self) -> ZoneKeys {
+        return ZoneKeys {
+            zone: self.name.clone(),
+            dnskeys: vec![self.ksk_dnskey(), self.zsk_dnskey()],
+        };
+    }
+
+    // the RRset followed by its RRSIG. labels is smaller than the owner's for wildcards
+    pub fn sign(&self, rrset: Vec<DnsResourceRecord>, labels: u8) -> Vec<DnsResourceRecord> {
+        let with_ksk = rrset[0].rr_type == DnsType::DNSKEY;
+        let dnskey = if with_ksk {
+            self.ksk_dnskey()
+        } else {
+            self.zsk_dnskey()
+        };
+        let mut rrsig = RData::RRSIG {
+            type_covered: rrset[0].rr_type,
+            algorithm: if with_ksk { 15 } else { 13 },
+            labels,
+            original_ttl: 3600,
+            expiration: NOW + 86400,
+            inception: NOW - 86400,
+            key_tag: compute_key_tag(&dnskey),
+            signer_name: self.name.clone(),
+            signature: vec![],
+        };
+
+        let data = create_signed_data(&rrset.iter().collect::<Vec<_>>(), &rrsig).unwrap();
+        let signed = if with_ksk {
+            self.ksk.sign(&data).as_ref().to_vec()
+        } else {
+            self.zsk
+                .sign(&SystemRandom::new(), &data)
+                .unwrap()
+                .as_ref()
+                .to_vec()
+        };
+        if let RData::RRSIG { signature, .. } = &mut rrsig {
+            *signature = signed;
+        }
+
+        let mut records = rrset.clone();
+        records.push(create_rr(&rrset[0].name.to_string(), DnsType::RRSIG, rrsig));
+        return records;
+    }
+
+    pub fn sign_dnskeys(&self) -> Vec<DnsResourceRecord> {
+        let name = self.name.to_string();
+        return self.sign(
+            vec![
+                create_rr(&name, DnsType::DNSKEY, self.ksk_dnskey()),
+                create_rr(&name, DnsType::DNSKEY, self.zsk_dnskey()),
+            ],
+            self.name.label_count() as u8,
+        );
+    }
+
+    pub fn create_nsec3(
+        &self,
+        name: &str,
+        next_name: &str,
+        flags: u8,
+        types: Vec<DnsType>,
+    ) -> DnsResourceRecord {
+        let hash = |name: &str| compute_nsec3_hash(&name.parse().unwrap(), &[0xab], 1);
+        return create_rr(
+            &format!("{}.{}", print_base32hex(&hash(name)), self.name),
+            DnsType::NSEC3,
+            RData::NSEC3 {
+                hash_algorithm: 1,
+                flags,
+                iterations: 1,
+                salt: vec![0xab],
+                next_hashed_owner_name: hash(next_name),
+                types,
+            },
+        );
+    }
+}
@@ -0,0 +1,493 @@
+#![allow(clippy::needless_return)]
+
+// JSON encoding of messages with the member names of rfc8427.
+// see: https://www.rfc-editor.org/rfc/rfc8427
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error, ser};
+use serde_json::{Map, Value};
+
+use super::{
+    encoding::{parse_hex, print_hex},
+    error::DnsSerializeError,
+    model::{
+        DnsClass, DnsHeaderOpecode, DnsHeaderRcode, DnsHeaderSection, DnsMessage,
+        DnsQuestionSectionEntry, DnsResourceRecord, DnsType, RData,
+    },
+    name::DomainName,
+    parser::{parse_dns_edns, parse_dns_rdata},
+    serializer::create_dns_opt_resource_record,
+    zone::parse_rdata_str,
+};
+
+// flags are written as booleans, 0 and 1 are accepted as well
+mod flag {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(value: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+        return serializer.serialize_bool(*value);
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        Number(u8),
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+        return match Flag::deserialize(deserializer)? {
+            Flag::Bool(value) => Ok(value),
+            Flag::Number(0) => Ok(false),
+            Flag::Number(1) => Ok(true),
+            Flag::Number(value) => Err(D::Error::custom(format!("bad flag: {}", value))),
+        };
+    }
+}
+
+// questions leave out TTL and the rdata. see: https://www.rfc-editor.org/rfc/rfc8427#section-2.2
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct JsonResourceRecord {
+    #[serde(rename = "NAME")]
+    name: String,
+    #[serde(rename = "TYPE")]
+    rr_type: u16,
+    #[serde(rename = "TYPEname", default, skip_serializing_if = "Option::is_none")]
+    type_name: Option<String>,
+    #[serde(rename = "CLASS")]
+    rr_class: u16,
+    #[serde(rename = "CLASSname", default, skip_serializing_if = "Option::is_none")]
+    class_name: Option<String>,
+    #[serde(rename = "TTL", default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<u32>,
+    #[serde(rename = "RDLENGTH", default, skip_serializing_if = "Option::is_none")]
+    rd_length: Option<u16>,
+    #[serde(rename = "rdataHEX", default, skip_serializing_if = "Option::is_none")]
+    rdata_hex: Option<String>,
+    // rdata<TYPEname> in presentation format, such as rdataA
+    #[serde(flatten)]
+    rdata: Map<String, Value>,
+}
+
+fn to_json_question(question: &DnsQuestionSectionEntry) -> JsonResourceRecord {
+    return JsonResourceRecord {
+        name: question.q_name.to_string(),
+        rr_type: question.q_type.into(),
+        type_name: Some(question.q_type.to_string()),
+        rr_class: question.q_class.into(),
+        class_name: Some(question.q_class.to_string()),
+        ttl: None,
+        rd_length: None,
+        rdata_hex: None,
+        rdata: Map::new(),
+    };
+}
+
+fn from_json_question(json: &JsonResourceRecord) -> Result<DnsQuestionSectionEntry, String> {
+    return Ok(DnsQuestionSectionEntry {
+        q_name: json.name.parse()?,
+        q_type: DnsType::from(json.rr_type),
+        q_class: DnsClass::from(json.rr_class),
+    });
+}
+
+fn to_json_resource_record(
+    rr: &DnsResourceRecord,
+) -> Result<JsonResourceRecord, DnsSerializeError> {
+    let data = rr.rdata.to_wire()?;
+
+    // OPT and opaque rdata have no presentation format of their own
+    let mut rdata = Map::new();
+    if !matches!(rr.rdata, RData::OPT(_) | RData::Unknown(_)) {
+        rdata.insert(
+            format!("rdata{}", rr.rr_type),
+            Value::String(rr.rdata.to_string()),
+        );
+    }
+
+    return Ok(JsonResourceRecord {
+        name: rr.name.to_string(),
+        rr_type: rr.rr_type.into(),
+        type_name: Some(rr.rr_type.to_string()),
+        rr_class: rr.rr_class.into(),
+        class_name: Some(rr.rr_class.to_string()),
+        ttl: Some(rr.ttl),
+        rd_length: Some(data.len() as u16),
+        rdata_hex: Some(print_hex(&data)),
+        rdata,
+    });
+}
+
+// rdataHEX is preferred, the presentation format is read when it is missing
+fn from_json_resource_record(json: &JsonResourceRecord) -> Result<DnsResourceRecord, String> {
+    let rr_type = DnsType::from(json.rr_type);
+
+    let rdata = match (
+        &json.rdata_hex,
+        json.rdata.get(&format!("rdata{}", rr_type)),
+    ) {
+        (Some(rdata_hex), _) => {
+            let data = parse_hex(rdata_hex)?;
+            if json
+                .rd_length
+                .is_some_and(|rd_length| rd_length as usize != data.len())
+            {
+                return Err(format!("RDLENGTH mismatch: {}", json.name));
+            }
+            parse_dns_rdata(&data, 0, &rr_type, data.len()).map_err(|error| error.to_string())?
+        }
+        (None, Some(Value::String(rdata_str))) => {
+            parse_rdata_str(rr_type, rdata_str, &DomainName::root())?
+        }
+        _ => return Err(format!("missing rdata: {}", json.name)),
+    };
+
+    return Ok(DnsResourceRecord {
+        name: json.name.parse()?,
+        rr_type,
+        rr_class: DnsClass::from(json.rr_class),
+        ttl: json.ttl.ok_or(format!("missing TTL: {}", json.name))?,
+        rdata,
+    });
+}
+
+// a single question is written inline, more of them as questionRRs.
+// see: https://www.rfc-editor.org/rfc/rfc8427#section-2.1
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct JsonMessage {
+    #[serde(rename = "ID")]
+    id: u16,
+    #[serde(rename = "QR", with = "flag")]
+    qr: bool,
+    #[serde(rename = "Opcode")]
+    opcode: u8,
+    #[serde(rename = "AA", with = "flag")]
+    aa: bool,
+    #[serde(rename = "TC", with = "flag")]
+    tc: bool,
+    #[serde(rename = "RD", with = "flag")]
+    rd: bool,
+    #[serde(rename = "RA", with = "flag")]
+    ra: bool,
+    #[serde(rename = "AD", with = "flag")]
+    ad: bool,
+    #[serde(rename = "CD", with = "flag")]
+    cd: bool,
+    // only the lower 4 bits, the rest is in the OPT record
+    #[serde(rename = "RCODE")]
+    rcode: u8,
+    #[serde(rename = "QDCOUNT")]
+    qdcount: u16,
+    #[serde(rename = "ANCOUNT")]
+    ancount: u16,
+    #[serde(rename = "NSCOUNT")]
+    nscount: u16,
+    #[serde(rename = "ARCOUNT")]
+    arcount: u16,
+    #[serde(rename = "QNAME", default, skip_serializing_if = "Option::is_none")]
+    q_name: Option<String>,
+    #[serde(rename = "QTYPE", default, skip_serializing_if = "Option::is_none")]
+    q_type: Option<u16>,
+    #[serde(rename = "QTYPEname", default, skip_serializing_if = "Option::is_none")]
+    q_type_name: Option<String>,
+    #[serde(rename = "QCLASS", default, skip_serializing_if = "Option::is_none")]
+    q_class: Option<u16>,
+    #[serde(
+        rename = "QCLASSname",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    q_class_name: Option<String>,
+    #[serde(rename = "questionRRs", default, skip_serializing_if = "Vec::is_empty")]
+    questions: Vec<JsonResourceRecord>,
+    #[serde(rename = "answerRRs", default, skip_serializing_if = "Vec::is_empty")]
+    answers: Vec<JsonResourceRecord>,
+    #[serde(
+        rename = "authorityRRs",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    authorities: Vec<JsonResourceRecord>,
+    // the OPT record is written back here
+    #[serde(
+        rename = "additionalRRs",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    additionals: Vec<JsonResourceRecord>,
+}
+
+fn to_json_message(message: &DnsMessage) -> Result<JsonMessage, DnsSerializeError> {
+    let header = &message.header;
+    let mut json = JsonMessage {
+        id: header.id,
+        qr: header.qr,
+        opcode: header.opcode.into(),
+        aa: header.aa,
+        tc: header.tc,
+        rd: header.rd,
+        ra: header.ra,
+        ad: header.ad,
+        cd: header.cd,
+        rcode: (u16::from(header.rcode) & 0x0f) as u8,
+        qdcount: header.qdcount,
+        ancount: header.ancount,
+        nscount: header.nscount,
+        arcount: header.arcount,
+        q_name: None,
+        q_type: None,
+        q_type_name: None,
+        q_class: None,
+        q_class_name: None,
+        questions: Vec::new(),
+        answers: message
+            .answers
+            .iter()
+            .map(to_json_resource_record)
+            .collect::<Result<Vec<JsonResourceRecord>, DnsSerializeError>>()?,
+        authorities: message
+            .authorities
+            .iter()
+            .map(to_json_resource_record)
+            .collect::<Result<Vec<JsonResourceRecord>, DnsSerializeError>>()?,
+        additionals: message
+            .additionals
+            .iter()
+            .map(to_json_resource_record)
+            .collect::<Result<Vec<JsonResourceRecord>, DnsSerializeError>>()?,
+    };
+
+    if let [question] = message.questions.as_slice() {
+        json.q_name = Some(question.q_name.to_string());
+        json.q_type = Some(question.q_type.into());
+        json.q_type_name = Some(question.q_type.to_string());
+        json.q_class = Some(question.q_class.into());
+        json.q_class_name = Some(question.q_class.to_string());
+    } else {
+        json.questions = message.questions.iter().map(to_json_question).collect();
+    }
+    if let Some(edns) = &message.edns {
+        json.additionals
+            .push(to_json_resource_record(&create_dns_opt_resource_record(
+                edns,
+            ))?);
+    }
+
+    return Ok(json);
+}
+
+fn from_json_message(json: &JsonMessage) -> Result<DnsMessage, String> {
+    let mut questions = json
+        .questions
+        .iter()
+        .map(from_json_question)
+        .collect::<Result<Vec<DnsQuestionSectionEntry>, String>>()?;
+    if let Some(q_name) = &json.q_name {
+        questions.insert(
+            0,
+            DnsQuestionSectionEntry {
+                q_name: q_name.parse()?,
+                q_type: DnsType::from(json.q_type.ok_or("missing QTYPE".to_string())?),
+                q_class: DnsClass::from(json.q_class.ok_or("missing QCLASS".to_string())?),
+            },
+        );
+    }
+
+    let parse_records = |records: &Vec<JsonResourceRecord>| {
+        return records
+            .iter()
+            .map(from_json_resource_record)
+            .collect::<Result<Vec<DnsResourceRecord>, String>>();
+    };
+    let mut additionals = Vec::new();
+    let mut edns = None;
+    for rr in parse_records(&json.additionals)? {
+        if rr.rr_type == DnsType::OPT {
+            if edns.is_some() || !rr.name.is_root() {
+                return Err("bad OPT record".to_string());
+            }
+            edns = Some(parse_dns_edns(rr));
+        } else {
+            additionals.push(rr);
+        }
+    }
+
+    return Ok(DnsMessage {
+        header: DnsHeaderSection {
+            id: json.id,
+            qr: json.qr,
+            opcode: DnsHeaderOpecode::from(json.opcode),
+            aa: json.aa,
+            tc: json.tc,
+            rd: json.rd,
+            ra: json.ra,
+            z: false,
+            ad: json.ad,
+            cd: json.cd,
+            rcode: DnsHeaderRcode::from(json.rcode as u16),
+            qdcount: json.qdcount,
+            ancount: json.ancount,
+            nscount: json.nscount,
+            arcount: json.arcount,
+        },
+        questions,
+        answers: parse_records(&json.answers)?,
+        authorities: parse_records(&json.authorities)?,
+        additionals,
+        edns,
+    });
+}
+
+impl Serialize for DnsMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return to_json_message(self)
+            .map_err(<S::Error as ser::Error>::custom)?
+            .serialize(serializer);
+    }
+}
+
+impl<'de> Deserialize<'de> for DnsMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        return from_json_message(&JsonMessage::deserialize(deserializer)?)
+            .map_err(D::Error::custom);
+    }
+}
+
+/// RFC 8427 JSON on one line, as written to log pipelines.
+///
+/// ```
+/// use rust_dns::dns::*;
+///
+/// let query = QueryBuilder::new(1)
+///     .question(&"example.com.".parse().unwrap(), DnsType::A, DnsClass::IN)
+///     .build();
+/// let json = print_json_message(&query).unwrap();
+/// assert!(json.contains(r#""QNAME":"example.com.""#));
+/// assert_eq!(parse_json_message(&json).unwrap(), query);
+/// ```
+pub fn print_json_message(message: &DnsMessage) -> Result<String, String> {
+    return serde_json::to_string(message).map_err(|error| error.to_string());
+}
+
+/// Reads RFC 8427 JSON. `rdataHEX` is used when present, else the presentation format
+/// such as `rdataA`.
+pub fn parse_json_message(json: &str) -> Result<DnsMessage, String> {
+    return serde_json::from_str(json).map_err(|error| error.to_string());
+}
+#[cfg(test)]
+mod json_message {
+    use std::net::Ipv4Addr;
+
+    use serde_json::{Value, json};
+
+    use crate::dns::{
+        builder::QueryBuilder,
+        json::{parse_json_message, print_json_message},
+        model::{
+            DnsClass, DnsHeaderRcode, DnsMessage, DnsResourceRecord, DnsType, Edns, EdnsOption,
+            RData,
+        },
+    };
+
+    fn create_reply() -> DnsMessage {
+        let mut message = QueryBuilder::new(19678)
+            .question(&"example.com.".parse().unwrap(), DnsType::A, DnsClass::IN)
+            .edns(Edns {
+                udp_payload_size: 1232,
+                extended_rcode: 0,
+                version: 0,
+                dnssec_ok: true,
+                options: vec![EdnsOption {
+                    code: 10,
+                    data: vec![0x01, 0x23],
+                }],
+            })
+            .build();
+        message.header.qr = true;
+        message.header.aa = true;
+        message.header.ancount = 1;
+        message.answers.push(DnsResourceRecord {
+            name: "example.com.".parse().unwrap(),
+            rr_type: DnsType::A,
+            rr_class: DnsClass::IN,
+            ttl: 86400,
+            rdata: RData::A(Ipv4Addr::new(192, 0, 2, 1)),
+        });
+
+        return message;
+    }
+
+    #[test]
+    fn case1() {
+        let message = create_reply();
+        let json: Value = serde_json::from_str(&print_json_message(&message).unwrap()).unwrap();
+
+        assert_eq!(
+            json,
+            json!({
+                "ID": 19678, "QR": true, "Opcode": 0, "AA": true, "TC": false,
+                "RD": false, "RA": false, "AD": false, "CD": false, "RCODE": 0,
+                "QDCOUNT": 1, "ANCOUNT": 1, "NSCOUNT": 0, "ARCOUNT": 1,
+                "QNAME": "example.com.", "QTYPE": 1, "QTYPEname": "A",
+                "QCLASS": 1, "QCLASSname": "IN",
+                "answerRRs": [{
+                    "NAME": "example.com.", "TYPE": 1, "TYPEname": "A",
+                    "CLASS": 1, "CLASSname": "IN", "TTL": 86400,
+                    "RDLENGTH": 4, "rdataHEX": "C0000201", "rdataA": "192.0.2.1"
+                }],
+                "additionalRRs": [{
+                    "NAME": ".", "TYPE": 41, "TYPEname": "OPT",
+                    "CLASS": 1232, "CLASSname": "CLASS1232", "TTL": 32768,
+                    "RDLENGTH": 6, "rdataHEX": "000A00020123"
+                }]
+            })
+        );
+        assert_eq!(
+            parse_json_message(&print_json_message(&message).unwrap()).unwrap(),
+            message
+        );
+    }
+
+    #[test]
+    fn case2() {
+        // from rfc8427 7.1, with presentation rdata instead of rdataHEX
+        let message = parse_json_message(
+            r#"{ "ID": 32784, "QR": 1, "Opcode": 0, "AA": 1, "TC": 0, "RD": 0,
+                 "RA": 0, "AD": 0, "CD": 0, "RCODE": 3, "QDCOUNT": 1,
+                 "ANCOUNT": 0, "NSCOUNT": 1, "ARCOUNT": 0,
+                 "QNAME": "example.com", "QTYPE": 1, "QCLASS": 1,
+                 "authorityRRs": [{ "NAME": "example.com", "TYPE": 6, "CLASS": 1, "TTL": 3600,
+                     "rdataSOA": "ns.example.com. hostmaster.example.com. 1 7200 3600 1209600 300" }] }"#,
+        )
+        .unwrap();
+
+        assert!(message.header.aa);
+        assert_eq!(message.header.rcode, DnsHeaderRcode::NoNameError);
+        assert_eq!(message.questions[0].q_name, "example.com.".parse().unwrap());
+        assert_eq!(message.authorities[0].rr_type, DnsType::SOA);
+        assert_eq!(
+            message.authorities[0].rdata.to_string(),
+            "ns.example.com. hostmaster.example.com. 1 7200 3600 1209600 300"
+        );
+    }
+
+    #[test]
+    fn case3() {
+        assert!(parse_json_message(r#"{ "ID": 1 }"#).is_err());
+        assert!(
+            parse_json_message(
+                r#"{ "ID": 1, "QR": 2, "Opcode": 0, "AA": 0, "TC": 0, "RD": 0, "RA": 0, "AD": 0,
+                     "CD": 0, "RCODE": 0, "QDCOUNT": 0, "ANCOUNT": 0, "NSCOUNT": 0, "ARCOUNT": 0 }"#
+            )
+            .is_err()
+        );
+        assert_eq!(
+            parse_json_message(
+                r#"{ "ID": 1, "QR": 1, "Opcode": 0, "AA": 0, "TC": 0, "RD": 0, "RA": 0, "AD": 0,
+                     "CD": 0, "RCODE": 0, "QDCOUNT": 0, "ANCOUNT": 1, "NSCOUNT": 0, "ARCOUNT": 0,
+                     "answerRRs": [{ "NAME": "a.", "TYPE": 1, "CLASS": 1, "TTL": 0,
+                                     "RDLENGTH": 3, "rdataHEX": "C0000201" }] }"#
+            )
+            .unwrap_err(),
+            "RDLENGTH mismatch: a."
+        );
+    }
+}
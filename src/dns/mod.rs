@@ -1,7 +1,35 @@
+//! DNS messages and records: the model, the wire format, presentation formats, zone files,
+//! JSON and DNSSEC validation.
+//!
+//! ```
+//! use rust_dns::dns::*;
+//!
+//! let records = parse_zone("www 3600 IN A 192.0.2.1\n", "example.zone", &"example.com.".parse().unwrap())
+//!     .unwrap();
+//! assert_eq!(print_zone(&records), "www.example.com.\t3600\tIN\tA\t192.0.2.1\n");
+//! ```
+mod builder;
+mod dnssec;
+mod encoding;
+mod error;
+#[cfg(test)]
+pub(crate) mod fixtures;
+mod json;
 mod model;
+mod name;
 mod parser;
+mod presentation;
+mod serializer;
 mod util;
+mod zone;
 
+pub use self::builder::*;
+pub use self::dnssec::*;
+pub use self::error::*;
+pub use self::json::*;
 pub use self::model::*;
+pub use self::name::*;
 pub use self::parser::*;
+pub use self::presentation::*;
 pub use self::util::*;
+pub use self::zone::*;
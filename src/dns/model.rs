@@ -1,12 +1,88 @@
+#![allow(clippy::needless_return)]
+
 use core::fmt;
+use std::{
+    net::{Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
 
-use num_derive::FromPrimitive;
+use super::{
+    encoding::{print_base32hex, print_base64, print_dnssec_time, print_hex},
+    name::DomainName,
+};
 
-#[derive(PartialEq, FromPrimitive, Debug)]
+// unknown types are kept as is and printed as TYPE<n>. see: https://www.rfc-editor.org/rfc/rfc3597#section-5
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 pub enum DnsType {
-    A = 1,
-    NS = 2,
-    CNAME = 5,
+    A,
+    NS,
+    CNAME,
+    SOA,
+    PTR,
+    MX,
+    TXT,
+    AAAA,
+    SRV,
+    OPT,
+    DS,
+    RRSIG,
+    NSEC,
+    DNSKEY,
+    NSEC3,
+    NSEC3PARAM,
+    ANY,
+    CAA,
+    Unknown(u16),
+}
+impl From<u16> for DnsType {
+    fn from(value: u16) -> Self {
+        match value {
+            1 => DnsType::A,
+            2 => DnsType::NS,
+            5 => DnsType::CNAME,
+            6 => DnsType::SOA,
+            12 => DnsType::PTR,
+            15 => DnsType::MX,
+            16 => DnsType::TXT,
+            28 => DnsType::AAAA,
+            33 => DnsType::SRV,
+            41 => DnsType::OPT,
+            43 => DnsType::DS,
+            46 => DnsType::RRSIG,
+            47 => DnsType::NSEC,
+            48 => DnsType::DNSKEY,
+            50 => DnsType::NSEC3,
+            51 => DnsType::NSEC3PARAM,
+            255 => DnsType::ANY,
+            257 => DnsType::CAA,
+            _ => DnsType::Unknown(value),
+        }
+    }
+}
+impl From<DnsType> for u16 {
+    fn from(value: DnsType) -> Self {
+        match value {
+            DnsType::A => 1,
+            DnsType::NS => 2,
+            DnsType::CNAME => 5,
+            DnsType::SOA => 6,
+            DnsType::PTR => 12,
+            DnsType::MX => 15,
+            DnsType::TXT => 16,
+            DnsType::AAAA => 28,
+            DnsType::SRV => 33,
+            DnsType::OPT => 41,
+            DnsType::DS => 43,
+            DnsType::RRSIG => 46,
+            DnsType::NSEC => 47,
+            DnsType::DNSKEY => 48,
+            DnsType::NSEC3 => 50,
+            DnsType::NSEC3PARAM => 51,
+            DnsType::ANY => 255,
+            DnsType::CAA => 257,
+            DnsType::Unknown(value) => value,
+        }
+    }
 }
 impl fmt::Display for DnsType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -14,27 +90,156 @@ impl fmt::Display for DnsType {
             DnsType::A => write!(f, "A"),
             DnsType::NS => write!(f, "NS"),
             DnsType::CNAME => write!(f, "CNAME"),
+            DnsType::SOA => write!(f, "SOA"),
+            DnsType::PTR => write!(f, "PTR"),
+            DnsType::MX => write!(f, "MX"),
+            DnsType::TXT => write!(f, "TXT"),
+            DnsType::AAAA => write!(f, "AAAA"),
+            DnsType::SRV => write!(f, "SRV"),
+            DnsType::OPT => write!(f, "OPT"),
+            DnsType::DS => write!(f, "DS"),
+            DnsType::RRSIG => write!(f, "RRSIG"),
+            DnsType::NSEC => write!(f, "NSEC"),
+            DnsType::DNSKEY => write!(f, "DNSKEY"),
+            DnsType::NSEC3 => write!(f, "NSEC3"),
+            DnsType::NSEC3PARAM => write!(f, "NSEC3PARAM"),
+            DnsType::ANY => write!(f, "ANY"),
+            DnsType::CAA => write!(f, "CAA"),
+            DnsType::Unknown(value) => write!(f, "TYPE{}", value),
         }
     }
 }
+impl FromStr for DnsType {
+    type Err = String;
 
-#[derive(PartialEq, FromPrimitive, Debug)]
+    // case-insensitive, TYPE<n> is accepted for every type
+    fn from_str(type_str: &str) -> Result<Self, Self::Err> {
+        let upper = type_str.to_ascii_uppercase();
+        if let Some(value) = upper.strip_prefix("TYPE")
+            && let Ok(value) = value.parse::<u16>()
+        {
+            return Ok(DnsType::from(value));
+        }
+
+        return match upper.as_str() {
+            "A" => Ok(DnsType::A),
+            "NS" => Ok(DnsType::NS),
+            "CNAME" => Ok(DnsType::CNAME),
+            "SOA" => Ok(DnsType::SOA),
+            "PTR" => Ok(DnsType::PTR),
+            "MX" => Ok(DnsType::MX),
+            "TXT" => Ok(DnsType::TXT),
+            "AAAA" => Ok(DnsType::AAAA),
+            "SRV" => Ok(DnsType::SRV),
+            "OPT" => Ok(DnsType::OPT),
+            "DS" => Ok(DnsType::DS),
+            "RRSIG" => Ok(DnsType::RRSIG),
+            "NSEC" => Ok(DnsType::NSEC),
+            "DNSKEY" => Ok(DnsType::DNSKEY),
+            "NSEC3" => Ok(DnsType::NSEC3),
+            "NSEC3PARAM" => Ok(DnsType::NSEC3PARAM),
+            "ANY" => Ok(DnsType::ANY),
+            "CAA" => Ok(DnsType::CAA),
+            _ => Err(format!("unknown type: {}", type_str)),
+        };
+    }
+}
+
+// unknown classes are kept as is and printed as CLASS<n>. see: https://www.rfc-editor.org/rfc/rfc3597#section-5
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 pub enum DnsClass {
-    IN = 1,
+    IN,
+    CH,
+    HS,
+    Unknown(u16),
+}
+impl From<u16> for DnsClass {
+    fn from(value: u16) -> Self {
+        match value {
+            1 => DnsClass::IN,
+            3 => DnsClass::CH,
+            4 => DnsClass::HS,
+            _ => DnsClass::Unknown(value),
+        }
+    }
+}
+impl From<DnsClass> for u16 {
+    fn from(value: DnsClass) -> Self {
+        match value {
+            DnsClass::IN => 1,
+            DnsClass::CH => 3,
+            DnsClass::HS => 4,
+            DnsClass::Unknown(value) => value,
+        }
+    }
 }
 impl fmt::Display for DnsClass {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             DnsClass::IN => write!(f, "IN"),
+            DnsClass::CH => write!(f, "CH"),
+            DnsClass::HS => write!(f, "HS"),
+            DnsClass::Unknown(value) => write!(f, "CLASS{}", value),
         }
     }
 }
+impl FromStr for DnsClass {
+    type Err = String;
 
-#[derive(PartialEq, FromPrimitive, Debug)]
+    // case-insensitive, CLASS<n> is accepted for every class
+    fn from_str(class_str: &str) -> Result<Self, Self::Err> {
+        let upper = class_str.to_ascii_uppercase();
+        if let Some(value) = upper.strip_prefix("CLASS")
+            && let Ok(value) = value.parse::<u16>()
+        {
+            return Ok(DnsClass::from(value));
+        }
+
+        return match upper.as_str() {
+            "IN" => Ok(DnsClass::IN),
+            "CH" => Ok(DnsClass::CH),
+            "HS" => Ok(DnsClass::HS),
+            _ => Err(format!("unknown class: {}", class_str)),
+        };
+    }
+}
+
+// see: https://www.iana.org/assignments/dns-parameters/dns-parameters.xhtml#dns-parameters-5
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum DnsHeaderOpecode {
-    Query = 0,
-    IQuery = 1,
-    Status = 2,
+    Query,
+    IQuery,
+    Status,
+    Notify,
+    Update,
+    DSO,
+    Unknown(u8),
+}
+impl From<u8> for DnsHeaderOpecode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => DnsHeaderOpecode::Query,
+            1 => DnsHeaderOpecode::IQuery,
+            2 => DnsHeaderOpecode::Status,
+            4 => DnsHeaderOpecode::Notify,
+            5 => DnsHeaderOpecode::Update,
+            6 => DnsHeaderOpecode::DSO,
+            _ => DnsHeaderOpecode::Unknown(value),
+        }
+    }
+}
+impl From<DnsHeaderOpecode> for u8 {
+    fn from(value: DnsHeaderOpecode) -> Self {
+        match value {
+            DnsHeaderOpecode::Query => 0,
+            DnsHeaderOpecode::IQuery => 1,
+            DnsHeaderOpecode::Status => 2,
+            DnsHeaderOpecode::Notify => 4,
+            DnsHeaderOpecode::Update => 5,
+            DnsHeaderOpecode::DSO => 6,
+            DnsHeaderOpecode::Unknown(value) => value,
+        }
+    }
 }
 impl fmt::Display for DnsHeaderOpecode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -42,18 +247,94 @@ impl fmt::Display for DnsHeaderOpecode {
             DnsHeaderOpecode::Query => write!(f, "Query"),
             DnsHeaderOpecode::IQuery => write!(f, "IQuery"),
             DnsHeaderOpecode::Status => write!(f, "Status"),
+            DnsHeaderOpecode::Notify => write!(f, "Notify"),
+            DnsHeaderOpecode::Update => write!(f, "Update"),
+            DnsHeaderOpecode::DSO => write!(f, "DSO"),
+            DnsHeaderOpecode::Unknown(value) => write!(f, "OPCODE{}", value),
         }
     }
 }
 
-#[derive(PartialEq, FromPrimitive, Debug)]
+// the header carries only the lower 4 bits, values above 15 need the EDNS extended rcode.
+// see: https://www.iana.org/assignments/dns-parameters/dns-parameters.xhtml#dns-parameters-6
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum DnsHeaderRcode {
-    NoError = 0,
-    FormatError = 1,
-    ServerFailure = 2,
-    NoNameError = 3,
-    NotImplemented = 4,
-    Refused = 5,
+    NoError,
+    FormatError,
+    ServerFailure,
+    NoNameError,
+    NotImplemented,
+    Refused,
+    YXDomain,
+    YXRRSet,
+    NXRRSet,
+    NotAuth,
+    NotZone,
+    DSOTypeNI,
+    BadVers,
+    BadKey,
+    BadTime,
+    BadMode,
+    BadName,
+    BadAlg,
+    BadTrunc,
+    BadCookie,
+    Unknown(u16),
+}
+impl From<u16> for DnsHeaderRcode {
+    fn from(value: u16) -> Self {
+        match value {
+            0 => DnsHeaderRcode::NoError,
+            1 => DnsHeaderRcode::FormatError,
+            2 => DnsHeaderRcode::ServerFailure,
+            3 => DnsHeaderRcode::NoNameError,
+            4 => DnsHeaderRcode::NotImplemented,
+            5 => DnsHeaderRcode::Refused,
+            6 => DnsHeaderRcode::YXDomain,
+            7 => DnsHeaderRcode::YXRRSet,
+            8 => DnsHeaderRcode::NXRRSet,
+            9 => DnsHeaderRcode::NotAuth,
+            10 => DnsHeaderRcode::NotZone,
+            11 => DnsHeaderRcode::DSOTypeNI,
+            // 16 is also BADSIG in TSIG records
+            16 => DnsHeaderRcode::BadVers,
+            17 => DnsHeaderRcode::BadKey,
+            18 => DnsHeaderRcode::BadTime,
+            19 => DnsHeaderRcode::BadMode,
+            20 => DnsHeaderRcode::BadName,
+            21 => DnsHeaderRcode::BadAlg,
+            22 => DnsHeaderRcode::BadTrunc,
+            23 => DnsHeaderRcode::BadCookie,
+            _ => DnsHeaderRcode::Unknown(value),
+        }
+    }
+}
+impl From<DnsHeaderRcode> for u16 {
+    fn from(value: DnsHeaderRcode) -> Self {
+        match value {
+            DnsHeaderRcode::NoError => 0,
+            DnsHeaderRcode::FormatError => 1,
+            DnsHeaderRcode::ServerFailure => 2,
+            DnsHeaderRcode::NoNameError => 3,
+            DnsHeaderRcode::NotImplemented => 4,
+            DnsHeaderRcode::Refused => 5,
+            DnsHeaderRcode::YXDomain => 6,
+            DnsHeaderRcode::YXRRSet => 7,
+            DnsHeaderRcode::NXRRSet => 8,
+            DnsHeaderRcode::NotAuth => 9,
+            DnsHeaderRcode::NotZone => 10,
+            DnsHeaderRcode::DSOTypeNI => 11,
+            DnsHeaderRcode::BadVers => 16,
+            DnsHeaderRcode::BadKey => 17,
+            DnsHeaderRcode::BadTime => 18,
+            DnsHeaderRcode::BadMode => 19,
+            DnsHeaderRcode::BadName => 20,
+            DnsHeaderRcode::BadAlg => 21,
+            DnsHeaderRcode::BadTrunc => 22,
+            DnsHeaderRcode::BadCookie => 23,
+            DnsHeaderRcode::Unknown(value) => value,
+        }
+    }
 }
 impl fmt::Display for DnsHeaderRcode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -64,11 +345,26 @@ impl fmt::Display for DnsHeaderRcode {
             DnsHeaderRcode::NoNameError => write!(f, "NoNameError"),
             DnsHeaderRcode::NotImplemented => write!(f, "NotImplemented"),
             DnsHeaderRcode::Refused => write!(f, "Refused"),
+            DnsHeaderRcode::YXDomain => write!(f, "YXDomain"),
+            DnsHeaderRcode::YXRRSet => write!(f, "YXRRSet"),
+            DnsHeaderRcode::NXRRSet => write!(f, "NXRRSet"),
+            DnsHeaderRcode::NotAuth => write!(f, "NotAuth"),
+            DnsHeaderRcode::NotZone => write!(f, "NotZone"),
+            DnsHeaderRcode::DSOTypeNI => write!(f, "DSOTypeNI"),
+            DnsHeaderRcode::BadVers => write!(f, "BadVers"),
+            DnsHeaderRcode::BadKey => write!(f, "BadKey"),
+            DnsHeaderRcode::BadTime => write!(f, "BadTime"),
+            DnsHeaderRcode::BadMode => write!(f, "BadMode"),
+            DnsHeaderRcode::BadName => write!(f, "BadName"),
+            DnsHeaderRcode::BadAlg => write!(f, "BadAlg"),
+            DnsHeaderRcode::BadTrunc => write!(f, "BadTrunc"),
+            DnsHeaderRcode::BadCookie => write!(f, "BadCookie"),
+            DnsHeaderRcode::Unknown(value) => write!(f, "RCODE{}", value),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct DnsHeaderSection {
     pub id: u16,
     pub qr: bool,
@@ -77,6 +373,10 @@ pub struct DnsHeaderSection {
     pub tc: bool,
     pub rd: bool,
     pub ra: bool,
+    // reserved, must be zero in queries
+    pub z: bool,
+    pub ad: bool,
+    pub cd: bool,
     pub rcode: DnsHeaderRcode,
     pub qdcount: u16,
     pub ancount: u16,
@@ -99,6 +399,9 @@ impl fmt::Display for DnsHeaderSection {
         write!(f, " {}", print_bool(self.tc, "tc"))?;
         write!(f, " {}", print_bool(self.rd, "rd"))?;
         write!(f, " {}", print_bool(self.ra, "ra"))?;
+        write!(f, " {}", print_bool(self.z, "z"))?;
+        write!(f, " {}", print_bool(self.ad, "ad"))?;
+        write!(f, " {}", print_bool(self.cd, "cd"))?;
         write!(f, " rcode={}", self.rcode)?;
         write!(f, " qdcount={}", self.qdcount)?;
         write!(f, " ancount={}", self.ancount)?;
@@ -107,9 +410,9 @@ impl fmt::Display for DnsHeaderSection {
     }
 }
 
-#[derive(Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct DnsQuestionSectionEntry {
-    pub q_name: String,
+    pub q_name: DomainName,
     pub q_type: DnsType,
     pub q_class: DnsClass,
 }
@@ -121,13 +424,272 @@ impl fmt::Display for DnsQuestionSectionEntry {
     }
 }
 
-#[derive(Debug)]
+// see: https://www.rfc-editor.org/rfc/rfc1035#section-5.1
+fn print_character_string(data: &[u8]) -> String {
+    let mut string = "\"".to_string();
+    for &byte in data {
+        match byte {
+            b'"' | b'\\' => {
+                string.push('\\');
+                string.push(byte as char);
+            }
+            0x20..=0x7e => string.push(byte as char),
+            _ => string.push_str(&format!("\\{:03}", byte)),
+        }
+    }
+    string.push('"');
+
+    return string;
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum RData {
+    A(Ipv4Addr),
+    NS(DomainName),
+    CNAME(DomainName),
+    SOA {
+        mname: DomainName,
+        rname: DomainName,
+        serial: u32,
+        refresh: u32,
+        retry: u32,
+        expire: u32,
+        minimum: u32,
+    },
+    PTR(DomainName),
+    MX {
+        preference: u16,
+        exchange: DomainName,
+    },
+    TXT(Vec<Vec<u8>>),
+    AAAA(Ipv6Addr),
+    SRV {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: DomainName,
+    },
+    CAA {
+        flags: u8,
+        tag: String,
+        value: Vec<u8>,
+    },
+    // only seen while parsing, the OPT pseudo-record is moved to DnsMessage.edns
+    OPT(Vec<EdnsOption>),
+    // see: https://www.rfc-editor.org/rfc/rfc4034
+    DS {
+        key_tag: u16,
+        algorithm: u8,
+        digest_type: u8,
+        digest: Vec<u8>,
+    },
+    RRSIG {
+        type_covered: DnsType,
+        algorithm: u8,
+        labels: u8,
+        original_ttl: u32,
+        // seconds since the epoch, modulo 2^32
+        expiration: u32,
+        inception: u32,
+        key_tag: u16,
+        signer_name: DomainName,
+        signature: Vec<u8>,
+    },
+    NSEC {
+        next_domain_name: DomainName,
+        types: Vec<DnsType>,
+    },
+    DNSKEY {
+        flags: u16,
+        protocol: u8,
+        algorithm: u8,
+        public_key: Vec<u8>,
+    },
+    // see: https://www.rfc-editor.org/rfc/rfc5155
+    NSEC3 {
+        hash_algorithm: u8,
+        flags: u8,
+        iterations: u16,
+        salt: Vec<u8>,
+        next_hashed_owner_name: Vec<u8>,
+        types: Vec<DnsType>,
+    },
+    NSEC3PARAM {
+        hash_algorithm: u8,
+        flags: u8,
+        iterations: u16,
+        salt: Vec<u8>,
+    },
+    // opaque rdata of unknown types. see: https://www.rfc-editor.org/rfc/rfc3597#section-5
+    Unknown(Vec<u8>),
+}
+// each type is preceded by a space, so an empty list prints nothing
+fn print_types(types: &[DnsType]) -> String {
+    return types.iter().map(|t| format!(" {}", t)).collect();
+}
+
+// an empty salt is written as "-"
+fn print_salt(salt: &[u8]) -> String {
+    if salt.is_empty() {
+        return "-".to_string();
+    }
+
+    return print_hex(salt);
+}
+
+impl fmt::Display for RData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RData::A(address) => write!(f, "{}", address),
+            RData::NS(name) => write!(f, "{}", name),
+            RData::CNAME(name) => write!(f, "{}", name),
+            RData::SOA {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            } => write!(
+                f,
+                "{} {} {} {} {} {} {}",
+                mname, rname, serial, refresh, retry, expire, minimum
+            ),
+            RData::PTR(name) => write!(f, "{}", name),
+            RData::MX {
+                preference,
+                exchange,
+            } => write!(f, "{} {}", preference, exchange),
+            RData::TXT(strings) => write!(
+                f,
+                "{}",
+                strings
+                    .iter()
+                    .map(|string| print_character_string(string))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            RData::AAAA(address) => write!(f, "{}", address),
+            RData::SRV {
+                priority,
+                weight,
+                port,
+                target,
+            } => write!(f, "{} {} {} {}", priority, weight, port, target),
+            RData::CAA { flags, tag, value } => {
+                write!(f, "{} {} {}", flags, tag, print_character_string(value))
+            }
+            RData::OPT(options) => write!(
+                f,
+                "{}",
+                options
+                    .iter()
+                    .map(|option| option.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            RData::DS {
+                key_tag,
+                algorithm,
+                digest_type,
+                digest,
+            } => write!(
+                f,
+                "{} {} {} {}",
+                key_tag,
+                algorithm,
+                digest_type,
+                print_hex(digest)
+            ),
+            RData::RRSIG {
+                type_covered,
+                algorithm,
+                labels,
+                original_ttl,
+                expiration,
+                inception,
+                key_tag,
+                signer_name,
+                signature,
+            } => write!(
+                f,
+                "{} {} {} {} {} {} {} {} {}",
+                type_covered,
+                algorithm,
+                labels,
+                original_ttl,
+                print_dnssec_time(*expiration),
+                print_dnssec_time(*inception),
+                key_tag,
+                signer_name,
+                print_base64(signature)
+            ),
+            RData::NSEC {
+                next_domain_name,
+                types,
+            } => write!(f, "{}{}", next_domain_name, print_types(types)),
+            RData::DNSKEY {
+                flags,
+                protocol,
+                algorithm,
+                public_key,
+            } => write!(
+                f,
+                "{} {} {} {}",
+                flags,
+                protocol,
+                algorithm,
+                print_base64(public_key)
+            ),
+            RData::NSEC3 {
+                hash_algorithm,
+                flags,
+                iterations,
+                salt,
+                next_hashed_owner_name,
+                types,
+            } => write!(
+                f,
+                "{} {} {} {} {}{}",
+                hash_algorithm,
+                flags,
+                iterations,
+                print_salt(salt),
+                print_base32hex(next_hashed_owner_name),
+                print_types(types)
+            ),
+            RData::NSEC3PARAM {
+                hash_algorithm,
+                flags,
+                iterations,
+                salt,
+            } => write!(
+                f,
+                "{} {} {} {}",
+                hash_algorithm,
+                flags,
+                iterations,
+                print_salt(salt)
+            ),
+            RData::Unknown(data) => {
+                write!(f, "\\# {}", data.len())?;
+                if !data.is_empty() {
+                    write!(f, " {}", print_hex(data))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
 pub struct DnsResourceRecord {
-    pub name: String,
+    pub name: DomainName,
     pub rr_type: DnsType,
     pub rr_class: DnsClass,
     pub ttl: u32,
-    pub rdata: String,
+    pub rdata: RData,
 }
 impl fmt::Display for DnsResourceRecord {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -139,13 +701,65 @@ impl fmt::Display for DnsResourceRecord {
     }
 }
 
-#[derive(Debug)]
+// see: https://www.rfc-editor.org/rfc/rfc6891#section-6.1.2
+#[derive(PartialEq, Debug, Clone)]
+pub struct EdnsOption {
+    pub code: u16,
+    pub data: Vec<u8>,
+}
+impl fmt::Display for EdnsOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:", self.code)?;
+        for byte in &self.data {
+            write!(f, "{:02x}", byte)?;
+        }
+
+        write!(f, "")
+    }
+}
+
+// contents of the OPT pseudo-record. see: https://www.rfc-editor.org/rfc/rfc6891#section-6.1.3
+#[derive(PartialEq, Debug, Clone)]
+pub struct Edns {
+    pub udp_payload_size: u16,
+    // upper 8 bits of the 12 bit rcode
+    pub extended_rcode: u8,
+    pub version: u8,
+    pub dnssec_ok: bool,
+    pub options: Vec<EdnsOption>,
+}
+impl fmt::Display for Edns {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "version={}", self.version)?;
+        write!(f, " {}", print_bool(self.dnssec_ok, "do"))?;
+        write!(f, " udp={}", self.udp_payload_size)?;
+        write!(f, " extended_rcode={}", self.extended_rcode)?;
+        for option in &self.options {
+            write!(f, " option={}", option)?;
+        }
+
+        write!(f, "")
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
 pub struct DnsMessage {
     pub header: DnsHeaderSection,
     pub questions: Vec<DnsQuestionSectionEntry>,
     pub answers: Vec<DnsResourceRecord>,
     pub authorities: Vec<DnsResourceRecord>,
+    // never contains the OPT pseudo-record, see edns
     pub additionals: Vec<DnsResourceRecord>,
+    pub edns: Option<Edns>,
+}
+impl DnsMessage {
+    // the full 12 bit rcode, combined with the upper bits from EDNS.
+    // see: https://www.rfc-editor.org/rfc/rfc6891#section-6.1.3
+    pub fn rcode(&self) -> DnsHeaderRcode {
+        let extended_rcode = self.edns.as_ref().map_or(0, |edns| edns.extended_rcode);
+
+        return DnsHeaderRcode::from((extended_rcode as u16) << 4 | u16::from(self.header.rcode));
+    }
 }
 impl fmt::Display for DnsMessage {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -171,6 +785,10 @@ impl fmt::Display for DnsMessage {
             write!(f, "\n\t{}", a)?;
         }
 
+        if let Some(edns) = &self.edns {
+            write!(f, "\nOPT Pseudosection:\n\t{}", edns)?;
+        }
+
         write!(f, "")
     }
 }
@@ -0,0 +1,475 @@
+#![allow(clippy::needless_return)]
+
+use core::fmt;
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use rand::random_bool;
+
+// see: https://www.rfc-editor.org/rfc/rfc1035#section-2.3.4
+const MAX_LABEL_LENGTH: usize = 63;
+const MAX_NAME_LENGTH: usize = 255;
+
+/// A domain name, always absolute. Comparison ignores ascii case as domain names do.
+/// see: <https://www.rfc-editor.org/rfc/rfc4343>
+///
+/// ```
+/// use rust_dns::dns::DomainName;
+///
+/// let name: DomainName = "WWW.Example.com".parse().unwrap();
+/// assert_eq!(name, "www.example.com.".parse().unwrap());
+/// assert_eq!(name.to_string(), "WWW.Example.com.");
+/// assert!(name.is_subdomain_of(&"com.".parse().unwrap()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DomainName {
+    labels: Vec<Vec<u8>>,
+}
+impl DomainName {
+    pub fn root() -> Self {
+        return DomainName { labels: Vec::new() };
+    }
+
+    pub fn from_labels(labels: Vec<Vec<u8>>) -> Result<Self, String> {
+        if labels.iter().any(|label| label.is_empty()) {
+            return Err("empty label".to_string());
+        }
+        if labels.iter().any(|label| label.len() > MAX_LABEL_LENGTH) {
+            return Err("too long label".to_string());
+        }
+
+        let name = DomainName { labels };
+        if name.wire_length() > MAX_NAME_LENGTH {
+            return Err("too long domain name".to_string());
+        }
+
+        return Ok(name);
+    }
+
+    // from the leftmost label, the root label is not included
+    pub fn labels(&self) -> impl DoubleEndedIterator<Item = &[u8]> {
+        return self.labels.iter().map(|label| label.as_slice());
+    }
+
+    pub fn label_count(&self) -> usize {
+        return self.labels.len();
+    }
+
+    pub fn is_root(&self) -> bool {
+        return self.labels.is_empty();
+    }
+
+    pub fn wire_length(&self) -> usize {
+        return self
+            .labels
+            .iter()
+            .map(|label| 1 + label.len())
+            .sum::<usize>()
+            + 1;
+    }
+
+    // uncompressed wire format
+    pub fn to_wire(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for label in &self.labels {
+            bytes.push(label.len() as u8);
+            bytes.extend_from_slice(label);
+        }
+        bytes.push(0);
+
+        return bytes;
+    }
+
+    pub fn to_lowercase(&self) -> DomainName {
+        return DomainName {
+            labels: self
+                .labels
+                .iter()
+                .map(|label| label.to_ascii_lowercase())
+                .collect(),
+        };
+    }
+
+    pub fn parent(&self) -> Option<DomainName> {
+        if self.is_root() {
+            return None;
+        }
+
+        return Some(DomainName {
+            labels: self.labels[1..].to_vec(),
+        });
+    }
+
+    // a name is a subdomain of itself
+    pub fn is_subdomain_of(&self, other: &DomainName) -> bool {
+        if self.labels.len() < other.labels.len() {
+            return false;
+        }
+
+        return self
+            .labels()
+            .rev()
+            .zip(other.labels().rev())
+            .all(|(a, b)| a.eq_ignore_ascii_case(b));
+    }
+
+    // every letter in upper or lower case at random, for servers to echo back.
+    // see: https://datatracker.ietf.org/doc/html/draft-vixie-dnsext-dns0x20-00
+    pub fn randomize_case(&self) -> DomainName {
+        return DomainName {
+            labels: self
+                .labels
+                .iter()
+                .map(|label| {
+                    return label
+                        .iter()
+                        .map(|byte| {
+                            if random_bool(0.5) {
+                                return byte.to_ascii_uppercase();
+                            } else {
+                                return byte.to_ascii_lowercase();
+                            }
+                        })
+                        .collect();
+                })
+                .collect(),
+        };
+    }
+
+    // unlike ==, the case of every letter has to match too
+    pub fn eq_case_sensitive(&self, other: &DomainName) -> bool {
+        return self.labels == other.labels;
+    }
+}
+#[cfg(test)]
+mod from_labels {
+    use crate::dns::name::DomainName;
+
+    #[test]
+    fn case1() {
+        let name = DomainName::from_labels(vec![b"example".to_vec(), b"com".to_vec()]).unwrap();
+        assert_eq!(name.to_string(), "example.com.");
+        assert_eq!(name.label_count(), 2);
+        assert_eq!(name.wire_length(), 13);
+    }
+
+    #[test]
+    fn case2() {
+        assert!(DomainName::from_labels(vec![b"example".to_vec(), vec![]]).is_err());
+        assert!(DomainName::from_labels(vec![vec![b'a'; 64]]).is_err());
+        assert!(DomainName::from_labels(vec![vec![b'a'; 63]; 4]).is_err());
+    }
+}
+#[cfg(test)]
+mod parent {
+    use crate::dns::name::DomainName;
+
+    #[test]
+    fn case1() {
+        let name: DomainName = "www.example.com.".parse().unwrap();
+
+        let parent = name.parent().unwrap();
+        assert_eq!(parent.to_string(), "example.com.");
+        assert_eq!(parent.parent().unwrap().to_string(), "com.");
+        assert!(parent.parent().unwrap().parent().unwrap().is_root());
+        assert_eq!(DomainName::root().parent(), None);
+    }
+}
+#[cfg(test)]
+mod is_subdomain_of {
+    use crate::dns::name::DomainName;
+
+    #[test]
+    fn case1() {
+        let name: DomainName = "www.Example.com.".parse().unwrap();
+
+        assert!(name.is_subdomain_of(&"example.COM.".parse().unwrap()));
+        assert!(name.is_subdomain_of(&"www.example.com.".parse().unwrap()));
+        assert!(name.is_subdomain_of(&DomainName::root()));
+        assert!(!name.is_subdomain_of(&"ample.com.".parse().unwrap()));
+        assert!(!name.is_subdomain_of(&"a.www.example.com.".parse().unwrap()));
+    }
+}
+#[cfg(test)]
+mod randomize_case {
+    use crate::dns::name::DomainName;
+
+    #[test]
+    fn case1() {
+        let name: DomainName = "www-1.example.com.".parse().unwrap();
+
+        let randomized: Vec<DomainName> = (0..16).map(|_| name.randomize_case()).collect();
+        assert!(randomized.iter().all(|randomized| randomized == &name));
+        assert!(
+            randomized
+                .iter()
+                .any(|randomized| !randomized.eq_case_sensitive(&name))
+        );
+        assert!(
+            randomized
+                .iter()
+                .all(|randomized| randomized.to_string().contains("-1."))
+        );
+    }
+}
+#[cfg(test)]
+mod eq_case_sensitive {
+    use crate::dns::name::DomainName;
+
+    #[test]
+    fn case1() {
+        let name: DomainName = "www.Example.com.".parse().unwrap();
+
+        assert!(name.eq_case_sensitive(&"www.Example.com".parse().unwrap()));
+        assert!(!name.eq_case_sensitive(&"www.example.com.".parse().unwrap()));
+        assert!(!name.eq_case_sensitive(&"example.com.".parse().unwrap()));
+    }
+}
+
+impl FromStr for DomainName {
+    type Err = String;
+
+    // every name is treated as absolute, the trailing dot is optional.
+    // \DDD and \X escapes are resolved. see: https://www.rfc-editor.org/rfc/rfc1035#section-5.1
+    fn from_str(name_str: &str) -> Result<Self, Self::Err> {
+        if name_str.is_empty() || name_str == "." {
+            return Ok(DomainName::root());
+        }
+
+        let bytes = name_str.as_bytes();
+        let mut labels = Vec::new();
+        let mut label = Vec::new();
+        let mut index = 0;
+        while index < bytes.len() {
+            match bytes[index] {
+                b'.' => {
+                    labels.push(std::mem::take(&mut label));
+                    index += 1;
+                }
+                b'\\' => {
+                    let escaped = &bytes[index + 1..];
+                    if escaped.len() >= 3 && escaped[..3].iter().all(|x| x.is_ascii_digit()) {
+                        let value = escaped[..3]
+                            .iter()
+                            .fold(0_u16, |value, x| value * 10 + (x - b'0') as u16);
+                        if value > 255 {
+                            return Err(format!("bad escape: {}", name_str));
+                        }
+                        label.push(value as u8);
+                        index += 4;
+                    } else if let Some(&x) = escaped.first()
+                        && !x.is_ascii_digit()
+                    {
+                        label.push(x);
+                        index += 2;
+                    } else {
+                        return Err(format!("bad escape: {}", name_str));
+                    }
+                }
+                x => {
+                    label.push(x);
+                    index += 1;
+                }
+            }
+        }
+        if !label.is_empty() {
+            labels.push(label);
+        }
+
+        return DomainName::from_labels(labels)
+            .map_err(|message| format!("{}: {}", message, name_str));
+    }
+}
+#[cfg(test)]
+mod from_str {
+    use crate::dns::name::DomainName;
+
+    #[test]
+    fn case1() {
+        let name: DomainName = "example.com.".parse().unwrap();
+        assert_eq!(
+            name.labels().collect::<Vec<&[u8]>>(),
+            vec![b"example".as_slice(), b"com".as_slice()]
+        );
+        assert_eq!(name, "example.com".parse().unwrap());
+    }
+
+    #[test]
+    fn case2() {
+        assert!("".parse::<DomainName>().unwrap().is_root());
+        assert!(".".parse::<DomainName>().unwrap().is_root());
+    }
+
+    #[test]
+    fn case3() {
+        assert!("example..com.".parse::<DomainName>().is_err());
+    }
+
+    #[test]
+    fn case4() {
+        let name: DomainName = "a\\.b\\065\\\\c\\000.com".parse().unwrap();
+        assert_eq!(
+            name.labels().collect::<Vec<&[u8]>>(),
+            vec![b"a.bA\\c\x00".as_slice(), b"com".as_slice()]
+        );
+    }
+
+    #[test]
+    fn case5() {
+        assert!("a\\256.com.".parse::<DomainName>().is_err());
+        assert!("a\\25.com.".parse::<DomainName>().is_err());
+        assert!("com\\".parse::<DomainName>().is_err());
+    }
+
+    #[test]
+    fn case6() {
+        // every octet survives a round trip through the presentation format
+        let labels: Vec<Vec<u8>> = (0..=255_u8)
+            .collect::<Vec<u8>>()
+            .chunks(63)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+        for label in labels {
+            let name = DomainName::from_labels(vec![label.clone()]).unwrap();
+            let parsed: DomainName = name.to_string().parse().unwrap();
+
+            assert_eq!(
+                parsed.labels().collect::<Vec<&[u8]>>(),
+                vec![label.as_slice()]
+            );
+        }
+    }
+}
+
+// see: https://www.rfc-editor.org/rfc/rfc1035#section-5.1
+fn print_label(label: &[u8]) -> String {
+    let mut string = "".to_string();
+    for &byte in label {
+        match byte {
+            b'.' | b'\\' | b'"' | b'(' | b')' | b';' | b'@' | b'$' => {
+                string.push('\\');
+                string.push(byte as char);
+            }
+            0x21..=0x7e => string.push(byte as char),
+            _ => string.push_str(&format!("\\{:03}", byte)),
+        }
+    }
+
+    return string;
+}
+
+impl fmt::Display for DomainName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_root() {
+            return write!(f, ".");
+        }
+
+        for label in &self.labels {
+            write!(f, "{}.", print_label(label))?;
+        }
+
+        write!(f, "")
+    }
+}
+#[cfg(test)]
+mod print_label {
+    use crate::dns::name::DomainName;
+
+    #[test]
+    fn case1() {
+        let name = DomainName::from_labels(vec![
+            b"a.b".to_vec(),
+            b"sp ace".to_vec(),
+            vec![0xff, b'\\', b'@'],
+            b"com".to_vec(),
+        ])
+        .unwrap();
+
+        assert_eq!(name.to_string(), "a\\.b.sp\\032ace.\\255\\\\\\@.com.");
+    }
+}
+
+impl PartialEq for DomainName {
+    fn eq(&self, other: &Self) -> bool {
+        return self.labels.len() == other.labels.len()
+            && self
+                .labels()
+                .zip(other.labels())
+                .all(|(a, b)| a.eq_ignore_ascii_case(b));
+    }
+}
+impl Eq for DomainName {}
+#[cfg(test)]
+mod eq {
+    use crate::dns::name::DomainName;
+
+    #[test]
+    fn case1() {
+        let name: DomainName = "EXAMPLE.com.".parse().unwrap();
+        assert_eq!(name, "example.COM.".parse().unwrap());
+        assert_ne!(name, "example.net.".parse().unwrap());
+        assert_ne!(name, "www.example.com.".parse().unwrap());
+    }
+}
+
+impl Hash for DomainName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_lowercase().labels.hash(state);
+    }
+}
+
+// canonical ordering. see: https://www.rfc-editor.org/rfc/rfc4034#section-6.1
+impl Ord for DomainName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        for (a, b) in self.labels().rev().zip(other.labels().rev()) {
+            let ordering = a.to_ascii_lowercase().cmp(&b.to_ascii_lowercase());
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+
+        return self.labels.len().cmp(&other.labels.len());
+    }
+}
+impl PartialOrd for DomainName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+#[cfg(test)]
+mod cmp {
+    use crate::dns::name::DomainName;
+
+    #[test]
+    fn case1() {
+        // from rfc4034 6.1
+        let sorted = vec![
+            DomainName::from_labels(vec![b"example".to_vec()]).unwrap(),
+            DomainName::from_labels(vec![b"a".to_vec(), b"example".to_vec()]).unwrap(),
+            DomainName::from_labels(vec![
+                b"yljkjljk".to_vec(),
+                b"a".to_vec(),
+                b"example".to_vec(),
+            ])
+            .unwrap(),
+            DomainName::from_labels(vec![b"Z".to_vec(), b"a".to_vec(), b"example".to_vec()])
+                .unwrap(),
+            DomainName::from_labels(vec![b"zABC".to_vec(), b"a".to_vec(), b"EXAMPLE".to_vec()])
+                .unwrap(),
+            DomainName::from_labels(vec![b"z".to_vec(), b"example".to_vec()]).unwrap(),
+            DomainName::from_labels(vec![vec![1], b"z".to_vec(), b"example".to_vec()]).unwrap(),
+            DomainName::from_labels(vec![b"*".to_vec(), b"z".to_vec(), b"example".to_vec()])
+                .unwrap(),
+            DomainName::from_labels(vec![vec![200], b"z".to_vec(), b"example".to_vec()]).unwrap(),
+        ];
+
+        let mut names = sorted.clone();
+        names.reverse();
+        names.sort();
+
+        assert_eq!(names, sorted);
+    }
+}
@@ -1,35 +1,101 @@
-use crate::util::print_ipaddr;
+#![allow(clippy::needless_return)]
 
-use super::model::{
-    DnsClass, DnsHeaderOpecode, DnsHeaderRcode, DnsHeaderSection, DnsMessage,
-    DnsQuestionSectionEntry, DnsResourceRecord, DnsType,
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use super::{
+    error::DnsParseError,
+    model::{
+        DnsClass, DnsHeaderOpecode, DnsHeaderRcode, DnsHeaderSection, DnsMessage,
+        DnsQuestionSectionEntry, DnsResourceRecord, DnsType, Edns, EdnsOption, RData,
+    },
+    name::DomainName,
 };
-use num::FromPrimitive;
 
-fn print_dns_domain_name(message: &Vec<u8>, offset: usize) -> (String, usize) {
-    let label_length = message[offset] as usize;
-    if label_length == 0 {
-        return ("".to_string(), 1);
-    }
+// see: https://www.rfc-editor.org/rfc/rfc1035#section-2.3.4
+const MAX_NAME_LENGTH: usize = 255;
+// a name has at most 127 labels, so a legitimate name never needs more hops than that
+const MAX_POINTER_HOPS: usize = 127;
 
-    if message[offset] & 0b11000000 != 0 {
-        let new_offset =
-            u16::from_be_bytes([message[offset] & 0b00111111, message[offset + 1]]) as usize;
-        let (name, _) = print_dns_domain_name(message, new_offset);
+// every parser takes the whole message, for the compression pointers
+#[allow(clippy::ptr_arg)]
+fn get_bytes(message: &Vec<u8>, offset: usize, length: usize) -> Result<&[u8], DnsParseError> {
+    return message
+        .get(offset..offset + length)
+        .ok_or(DnsParseError::Truncated(offset));
+}
 
-        return (name, 2);
-    };
+fn parse_u8(message: &Vec<u8>, offset: usize) -> Result<u8, DnsParseError> {
+    return Ok(get_bytes(message, offset, 1)?[0]);
+}
 
-    let label = std::str::from_utf8(&message[offset + 1..=offset + label_length])
-        .unwrap()
-        .to_string();
-    let (rest_name, rest_length) = print_dns_domain_name(message, offset + label_length + 1);
+fn parse_u16(message: &Vec<u8>, offset: usize) -> Result<u16, DnsParseError> {
+    let bytes = get_bytes(message, offset, 2)?;
+    return Ok(u16::from_be_bytes([bytes[0], bytes[1]]));
+}
 
-    return (label + "." + &rest_name, 1 + label_length + rest_length);
+fn parse_u32(message: &Vec<u8>, offset: usize) -> Result<u32, DnsParseError> {
+    let bytes = get_bytes(message, offset, 4)?;
+    return Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+}
+
+fn print_dns_domain_name(
+    message: &Vec<u8>,
+    offset: usize,
+) -> Result<(DomainName, usize), DnsParseError> {
+    let mut labels = Vec::new();
+    let mut name_length = 1; // root label
+    let mut position = offset;
+    // pointers must jump strictly before the place the current run of labels started,
+    // which makes loops impossible
+    let mut segment_start = offset;
+    let mut size = None;
+    let mut hops = 0;
+
+    loop {
+        let label_length = parse_u8(message, position)? as usize;
+        match label_length & 0b11000000 {
+            0b00000000 => {}
+            0b11000000 => {
+                let pointer = (parse_u16(message, position)? & 0b00111111_11111111) as usize;
+                if pointer >= segment_start {
+                    return Err(DnsParseError::BadPointer(position));
+                }
+
+                hops += 1;
+                if hops > MAX_POINTER_HOPS {
+                    return Err(DnsParseError::PointerLoop(position));
+                }
+
+                if size.is_none() {
+                    size = Some(position + 2 - offset);
+                }
+                position = pointer;
+                segment_start = pointer;
+                continue;
+            }
+            _ => return Err(DnsParseError::BadLabel(position)),
+        };
+
+        if label_length == 0 {
+            break;
+        }
+
+        name_length += 1 + label_length;
+        if name_length > MAX_NAME_LENGTH {
+            return Err(DnsParseError::NameTooLong(offset));
+        }
+
+        labels.push(get_bytes(message, position + 1, label_length)?.to_vec());
+        position += 1 + label_length;
+    }
+
+    let name = DomainName::from_labels(labels).map_err(|_| DnsParseError::NameTooLong(offset))?;
+
+    return Ok((name, size.unwrap_or_else(|| position + 1 - offset)));
 }
 #[cfg(test)]
 mod print_dns_domain_name {
-    use crate::dns::parser::print_dns_domain_name;
+    use crate::dns::{error::DnsParseError, parser::print_dns_domain_name};
 
     // from rfc1035 4.1.4. see: https://jprs.jp/tech/material/rfc/RFC1035-ja.txt
     const TEST_MESSAGE: [u8; 93] = [
@@ -46,47 +112,120 @@ mod print_dns_domain_name {
 
     #[test]
     fn case1() {
-        let (name, size) = print_dns_domain_name(&TEST_MESSAGE.to_vec(), 20);
-        assert_eq!(name, "F.ISI.ARPA.");
+        let (name, size) = print_dns_domain_name(&TEST_MESSAGE.to_vec(), 20).unwrap();
+        assert_eq!(name.to_string(), "F.ISI.ARPA.");
         assert_eq!(size, 12);
     }
 
     #[test]
     fn case2() {
-        let (name, size) = print_dns_domain_name(&TEST_MESSAGE.to_vec(), 40);
-        assert_eq!(name, "FOO.F.ISI.ARPA.");
+        let (name, size) = print_dns_domain_name(&TEST_MESSAGE.to_vec(), 40).unwrap();
+        assert_eq!(name.to_string(), "FOO.F.ISI.ARPA.");
         assert_eq!(size, 6);
     }
 
     #[test]
     fn case3() {
-        let (name, size) = print_dns_domain_name(&TEST_MESSAGE.to_vec(), 64);
-        assert_eq!(name, "ARPA.");
+        let (name, size) = print_dns_domain_name(&TEST_MESSAGE.to_vec(), 64).unwrap();
+        assert_eq!(name.to_string(), "ARPA.");
         assert_eq!(size, 2);
     }
 
     #[test]
     fn case4() {
-        let (name, size) = print_dns_domain_name(&TEST_MESSAGE.to_vec(), 92);
-        assert_eq!(name, "");
+        let (name, size) = print_dns_domain_name(&TEST_MESSAGE.to_vec(), 92).unwrap();
+        assert_eq!(name.to_string(), ".");
         assert_eq!(size, 1);
     }
+
+    #[test]
+    fn case5() {
+        let error = print_dns_domain_name(&vec![3, b'F', b'O'], 0).unwrap_err();
+        assert_eq!(error, DnsParseError::Truncated(1));
+    }
+
+    #[test]
+    fn case6() {
+        let error = print_dns_domain_name(&vec![0b01000000, 0], 0).unwrap_err();
+        assert_eq!(error, DnsParseError::BadLabel(0));
+    }
+
+    #[test]
+    fn case7() {
+        let (name, size) = print_dns_domain_name(&vec![2, 0xff, 0xfe, 0], 0).unwrap();
+        assert_eq!(
+            name.labels().collect::<Vec<&[u8]>>(),
+            vec![[0xff, 0xfe].as_slice()]
+        );
+        assert_eq!(name.to_string(), "\\255\\254.");
+        assert_eq!(size, 4);
+    }
+
+    #[test]
+    fn case8() {
+        let error = print_dns_domain_name(&vec![0b11000000, 10], 0).unwrap_err();
+        assert_eq!(error, DnsParseError::BadPointer(0));
+    }
+
+    #[test]
+    fn case9() {
+        let error = print_dns_domain_name(&vec![1, b'a', 0b11000000, 0], 0).unwrap_err();
+        assert_eq!(error, DnsParseError::BadPointer(2));
+    }
+
+    #[test]
+    fn case10() {
+        let mut message = vec![0; 12]; // header
+        message.extend_from_slice(&[0b11000000, 12]); // points to itself
+
+        let error = print_dns_domain_name(&message, 12).unwrap_err();
+        assert_eq!(error, DnsParseError::BadPointer(12));
+    }
+
+    #[test]
+    fn case11() {
+        let mut message = vec![0, 0]; // root
+        for offset in 1..200_u16 {
+            // every pointer jumps to the previous one
+            message.extend_from_slice(&(0b11000000_00000000 | ((offset - 1) * 2)).to_be_bytes());
+        }
+
+        let error = print_dns_domain_name(&message, 398).unwrap_err();
+        assert_eq!(error, DnsParseError::PointerLoop(144));
+    }
+
+    #[test]
+    fn case12() {
+        let mut message = vec![];
+        for _ in 0..5 {
+            message.push(63);
+            message.extend_from_slice(&[b'a'; 63]);
+        }
+        message.push(0);
+
+        let error = print_dns_domain_name(&message, 0).unwrap_err();
+        assert_eq!(error, DnsParseError::NameTooLong(0));
+    }
 }
 
-fn parse_dns_header_section(message: &Vec<u8>) -> Result<DnsHeaderSection, String> {
-    let id = u16::from_be_bytes([message[0], message[1]]);
-    let qr = message[2] & 0b10000000 != 0;
-    let opcode = DnsHeaderOpecode::from_u8((message[2] & 0b01111000) >> 3)
-        .ok_or("unknown opecode".to_string())?;
-    let aa = message[2] & 0b00000100 != 0;
-    let tc = message[2] & 0b00000010 != 0;
-    let rd = message[2] & 0b00000001 != 0;
-    let ra = message[3] & 0b10000000 != 0;
-    let rcode = DnsHeaderRcode::from_u8(message[3] & 0b00001111).ok_or("unknown rcode")?;
-    let qdcount = u16::from_be_bytes([message[4], message[5]]);
-    let ancount = u16::from_be_bytes([message[6], message[7]]);
-    let nscount = u16::from_be_bytes([message[8], message[9]]);
-    let arcount = u16::from_be_bytes([message[10], message[11]]);
+fn parse_dns_header_section(message: &Vec<u8>) -> Result<DnsHeaderSection, DnsParseError> {
+    let header = get_bytes(message, 0, 12)?;
+
+    let id = u16::from_be_bytes([header[0], header[1]]);
+    let qr = header[2] & 0b10000000 != 0;
+    let opcode = DnsHeaderOpecode::from((header[2] & 0b01111000) >> 3);
+    let aa = header[2] & 0b00000100 != 0;
+    let tc = header[2] & 0b00000010 != 0;
+    let rd = header[2] & 0b00000001 != 0;
+    let ra = header[3] & 0b10000000 != 0;
+    let z = header[3] & 0b01000000 != 0;
+    let ad = header[3] & 0b00100000 != 0;
+    let cd = header[3] & 0b00010000 != 0;
+    let rcode = DnsHeaderRcode::from((header[3] & 0b00001111) as u16);
+    let qdcount = u16::from_be_bytes([header[4], header[5]]);
+    let ancount = u16::from_be_bytes([header[6], header[7]]);
+    let nscount = u16::from_be_bytes([header[8], header[9]]);
+    let arcount = u16::from_be_bytes([header[10], header[11]]);
 
     return Ok(DnsHeaderSection {
         id,
@@ -96,6 +235,9 @@ fn parse_dns_header_section(message: &Vec<u8>) -> Result<DnsHeaderSection, Strin
         tc,
         rd,
         ra,
+        z,
+        ad,
+        cd,
         rcode,
         qdcount,
         ancount,
@@ -104,8 +246,10 @@ fn parse_dns_header_section(message: &Vec<u8>) -> Result<DnsHeaderSection, Strin
     });
 }
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod parse_dns_header_section {
     use crate::dns::{
+        error::DnsParseError,
         model::{DnsHeaderOpecode, DnsHeaderRcode},
         parser::parse_dns_header_section,
     };
@@ -130,6 +274,9 @@ mod parse_dns_header_section {
         assert_eq!(header.tc, false);
         assert_eq!(header.rd, false);
         assert_eq!(header.ra, false);
+        assert_eq!(header.z, false);
+        assert_eq!(header.ad, false);
+        assert_eq!(header.cd, false);
         assert_eq!(header.rcode, DnsHeaderRcode::NoError);
         assert_eq!(header.qdcount, 2);
         assert_eq!(header.ancount, 3);
@@ -138,36 +285,63 @@ mod parse_dns_header_section {
     }
 
     #[test]
-    #[should_panic]
     fn case2() {
-        parse_dns_header_section(&vec![
+        let header = parse_dns_header_section(&vec![
             0x00, 0x01,       // id
             0b11110100, // qr opecode=unknown! aa !tc !rd
-            0b00000000, // !ra ZZZ rcode=NoError
+            0b01111001, // !ra z ad cd rcode=NotAuth
             0x00, 0x02, // qdcount
             0x00, 0x03, // ancount
             0x00, 0x04, // nscount
             0x00, 0x05, // arcount
         ])
         .unwrap();
+
+        assert_eq!(header.opcode, DnsHeaderOpecode::Unknown(14));
+        assert_eq!(header.z, true);
+        assert_eq!(header.ad, true);
+        assert_eq!(header.cd, true);
+        assert_eq!(header.rcode, DnsHeaderRcode::NotAuth);
+    }
+
+    #[test]
+    fn case3() {
+        let error = parse_dns_header_section(&vec![
+            0x00, 0x01,       // id
+            0b10000100, // qr opecode=Query aa !tc !rd
+            0b00000000, // !ra ZZZ rcode=NoError
+            0x00, 0x02, // qdcount
+        ])
+        .unwrap_err();
+
+        assert_eq!(error, DnsParseError::Truncated(0));
+    }
+
+    #[test]
+    fn case4() {
+        let header = parse_dns_header_section(&vec![
+            0x00, 0x01,       // id
+            0b00101000, // !qr opecode=Update !aa !tc !rd
+            0b00000110, // !ra ZZZ rcode=YXDomain
+            0x00, 0x00, // qdcount
+            0x00, 0x00, // ancount
+            0x00, 0x00, // nscount
+            0x00, 0x00, // arcount
+        ])
+        .unwrap();
+
+        assert_eq!(header.opcode, DnsHeaderOpecode::Update);
+        assert_eq!(header.rcode, DnsHeaderRcode::YXDomain);
     }
 }
 
 fn parse_dns_question_section_entry(
     message: &Vec<u8>,
     offset: usize,
-) -> Result<(DnsQuestionSectionEntry, usize), (String, usize)> {
-    let (q_name, q_name_length) = print_dns_domain_name(message, offset);
-    let q_type = DnsType::from_u16(u16::from_be_bytes([
-        message[offset + q_name_length],
-        message[offset + q_name_length + 1],
-    ]))
-    .ok_or(("unknown q_type".to_string(), q_name_length + 4))?;
-    let q_class = DnsClass::from_u16(u16::from_be_bytes([
-        message[offset + q_name_length + 2],
-        message[offset + q_name_length + 3],
-    ]))
-    .ok_or(("unknown q_class".to_string(), q_name_length + 4))?;
+) -> Result<(DnsQuestionSectionEntry, usize), DnsParseError> {
+    let (q_name, q_name_length) = print_dns_domain_name(message, offset)?;
+    let q_type = DnsType::from(parse_u16(message, offset + q_name_length)?);
+    let q_class = DnsClass::from(parse_u16(message, offset + q_name_length + 2)?);
 
     return Ok((
         DnsQuestionSectionEntry {
@@ -197,7 +371,7 @@ mod parse_dns_question_section_entry {
         )
         .unwrap();
 
-        assert_eq!(entry.q_name, "example.com.");
+        assert_eq!(entry.q_name.to_string(), "example.com.");
         assert_eq!(entry.q_type, DnsType::A);
         assert_eq!(entry.q_class, DnsClass::IN);
         assert_eq!(size, 17);
@@ -205,60 +379,831 @@ mod parse_dns_question_section_entry {
 
     #[test]
     fn case2() {
-        let (name, size) = parse_dns_question_section_entry(
+        let (entry, size) = parse_dns_question_section_entry(
             &vec![
                 0, 0, 0, 0, // offset
                 7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0, // qname
-                0x00, 0xff, // unknown qtype!
-                0x00, 0x01, // qclass
+                0x30, 0x39, // unknown qtype!
+                0x00, 0x63, // unknown qclass!
             ],
             4,
         )
-        .unwrap_err();
+        .unwrap();
 
-        assert_eq!(name, "unknown q_type".to_string());
+        assert_eq!(entry.q_type, DnsType::Unknown(12345));
+        assert_eq!(entry.q_class, DnsClass::Unknown(99));
+        assert_eq!(entry.to_string(), "example.com.\tCLASS99\tTYPE12345");
         assert_eq!(size, 17);
     }
 }
 
-fn parse_dns_resource_record(
+// rdata must be consumed exactly, otherwise the record is malformed
+fn check_rdata_length(offset: usize, length: usize, rd_length: usize) -> Result<(), DnsParseError> {
+    if length != rd_length {
+        return Err(DnsParseError::BadRdata(offset));
+    }
+
+    return Ok(());
+}
+
+fn parse_dns_rdata_a(
+    message: &Vec<u8>,
+    offset: usize,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    check_rdata_length(offset, 4, rd_length)?;
+    let bytes = get_bytes(message, offset, 4)?;
+
+    return Ok(RData::A(Ipv4Addr::new(
+        bytes[0], bytes[1], bytes[2], bytes[3],
+    )));
+}
+
+fn parse_dns_rdata_aaaa(
+    message: &Vec<u8>,
+    offset: usize,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    check_rdata_length(offset, 16, rd_length)?;
+    let mut octets = [0_u8; 16];
+    octets.copy_from_slice(get_bytes(message, offset, 16)?);
+
+    return Ok(RData::AAAA(Ipv6Addr::from(octets)));
+}
+#[cfg(test)]
+mod parse_dns_rdata_aaaa {
+    use crate::dns::{error::DnsParseError, model::RData, parser::parse_dns_rdata_aaaa};
+
+    #[test]
+    fn case1() {
+        let rdata = parse_dns_rdata_aaaa(
+            &vec![
+                0, 0, // offset
+                0x26, 0x00, 0x14, 0x80, 0xe8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0xc0, // address
+            ],
+            2,
+            16,
+        )
+        .unwrap();
+
+        assert_eq!(rdata, RData::AAAA("2600:1480:e800::c0".parse().unwrap()));
+        assert_eq!(rdata.to_string(), "2600:1480:e800::c0");
+    }
+
+    #[test]
+    fn case2() {
+        let error = parse_dns_rdata_aaaa(&vec![0x26, 0x00, 0x14, 0x80], 0, 4).unwrap_err();
+
+        assert_eq!(error, DnsParseError::BadRdata(0));
+    }
+}
+
+fn parse_dns_rdata_soa(
+    message: &Vec<u8>,
+    offset: usize,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    let (mname, mname_length) = print_dns_domain_name(message, offset)?;
+    let (rname, rname_length) = print_dns_domain_name(message, offset + mname_length)?;
+    check_rdata_length(offset, mname_length + rname_length + 20, rd_length)?;
+    let offset = offset + mname_length + rname_length;
+
+    return Ok(RData::SOA {
+        mname,
+        rname,
+        serial: parse_u32(message, offset)?,
+        refresh: parse_u32(message, offset + 4)?,
+        retry: parse_u32(message, offset + 8)?,
+        expire: parse_u32(message, offset + 12)?,
+        minimum: parse_u32(message, offset + 16)?,
+    });
+}
+#[cfg(test)]
+mod parse_dns_rdata_soa {
+    use crate::dns::parser::parse_dns_rdata_soa;
+
+    #[test]
+    fn case1() {
+        let rdata = parse_dns_rdata_soa(
+            &vec![
+                3, b'c', b'o', b'm', 0, // com.
+                2, b'n', b's', 0b11000000, 0, // ns.com.
+                4, b'r', b'o', b'o', b't', 0b11000000, 0, // root.com.
+                0x78, 0x76, 0x4a, 0x5b, // serial
+                0x00, 0x00, 0x07, 0x08, // refresh
+                0x00, 0x00, 0x03, 0x84, // retry
+                0x00, 0x09, 0x3a, 0x80, // expire
+                0x00, 0x00, 0x0e, 0x10, // minimum
+            ],
+            5,
+            32,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rdata.to_string(),
+            "ns.com. root.com. 2021018203 1800 900 604800 3600"
+        );
+    }
+}
+
+fn parse_dns_rdata_mx(
+    message: &Vec<u8>,
+    offset: usize,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    let preference = parse_u16(message, offset)?;
+    let (exchange, exchange_length) = print_dns_domain_name(message, offset + 2)?;
+    check_rdata_length(offset, 2 + exchange_length, rd_length)?;
+
+    return Ok(RData::MX {
+        preference,
+        exchange,
+    });
+}
+#[cfg(test)]
+mod parse_dns_rdata_mx {
+    use crate::dns::{model::RData, parser::parse_dns_rdata_mx};
+
+    #[test]
+    fn case1() {
+        let rdata = parse_dns_rdata_mx(
+            &vec![
+                0x00, 10, // preference
+                4, b'm', b'a', b'i', b'l', 3, b'c', b'o', b'm', 0, // exchange
+            ],
+            0,
+            12,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rdata,
+            RData::MX {
+                preference: 10,
+                exchange: "mail.com.".parse().unwrap()
+            }
+        );
+        assert_eq!(rdata.to_string(), "10 mail.com.");
+    }
+}
+
+fn parse_dns_rdata_txt(
+    message: &Vec<u8>,
+    offset: usize,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    let mut strings = Vec::new();
+    let mut string_offset = offset;
+    while string_offset < offset + rd_length {
+        let string_length = parse_u8(message, string_offset)? as usize;
+        if string_offset + 1 + string_length > offset + rd_length {
+            return Err(DnsParseError::BadRdata(string_offset));
+        }
+        strings.push(get_bytes(message, string_offset + 1, string_length)?.to_vec());
+        string_offset += 1 + string_length;
+    }
+
+    return Ok(RData::TXT(strings));
+}
+#[cfg(test)]
+mod parse_dns_rdata_txt {
+    use crate::dns::{error::DnsParseError, model::RData, parser::parse_dns_rdata_txt};
+
+    #[test]
+    fn case1() {
+        let rdata = parse_dns_rdata_txt(
+            &vec![
+                5, b'h', b'e', b'l', b'l', b'o', // hello
+                6, b's', b'a', b'y', b' ', b'"', 0x07, // say "\007
+                0,    // empty
+            ],
+            0,
+            14,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rdata,
+            RData::TXT(vec![b"hello".to_vec(), b"say \"\x07".to_vec(), vec![]])
+        );
+        assert_eq!(rdata.to_string(), "\"hello\" \"say \\\"\\007\" \"\"");
+    }
+
+    #[test]
+    fn case2() {
+        let error = parse_dns_rdata_txt(
+            &vec![
+                5, b'h', b'e', b'l', b'l', b'o', // hello
+                4, b'o', b'v', b'e', b'r', // string overruns rdata
+            ],
+            0,
+            8,
+        )
+        .unwrap_err();
+
+        assert_eq!(error, DnsParseError::BadRdata(6));
+    }
+}
+
+fn parse_dns_rdata_srv(
+    message: &Vec<u8>,
+    offset: usize,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    let priority = parse_u16(message, offset)?;
+    let weight = parse_u16(message, offset + 2)?;
+    let port = parse_u16(message, offset + 4)?;
+    let (target, target_length) = print_dns_domain_name(message, offset + 6)?;
+    check_rdata_length(offset, 6 + target_length, rd_length)?;
+
+    return Ok(RData::SRV {
+        priority,
+        weight,
+        port,
+        target,
+    });
+}
+#[cfg(test)]
+mod parse_dns_rdata_srv {
+    use crate::dns::parser::parse_dns_rdata_srv;
+
+    #[test]
+    fn case1() {
+        let rdata = parse_dns_rdata_srv(
+            &vec![
+                0x00, 0x01, // priority
+                0x00, 0x05, // weight
+                0x14, 0x95, // port
+                3, b's', b'i', b'p', 3, b'c', b'o', b'm', 0, // target
+            ],
+            0,
+            15,
+        )
+        .unwrap();
+
+        assert_eq!(rdata.to_string(), "1 5 5269 sip.com.");
+    }
+}
+
+fn parse_dns_rdata_caa(
+    message: &Vec<u8>,
+    offset: usize,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    let flags = parse_u8(message, offset)?;
+    let tag_length = parse_u8(message, offset + 1)? as usize;
+    if 2 + tag_length > rd_length {
+        return Err(DnsParseError::BadRdata(offset));
+    }
+    let tag = String::from_utf8_lossy(get_bytes(message, offset + 2, tag_length)?).to_string();
+    let value = get_bytes(message, offset + 2 + tag_length, rd_length - 2 - tag_length)?.to_vec();
+
+    return Ok(RData::CAA { flags, tag, value });
+}
+#[cfg(test)]
+mod parse_dns_rdata_caa {
+    use crate::dns::{model::RData, parser::parse_dns_rdata_caa};
+
+    #[test]
+    fn case1() {
+        let mut message = vec![
+            0, // flags
+            5, // tag length
+            b'i', b's', b's', b'u', b'e', // tag
+        ];
+        message.extend_from_slice(b"letsencrypt.org"); // value
+
+        let rdata = parse_dns_rdata_caa(&message, 0, message.len()).unwrap();
+
+        assert_eq!(
+            rdata,
+            RData::CAA {
+                flags: 0,
+                tag: "issue".to_string(),
+                value: b"letsencrypt.org".to_vec()
+            }
+        );
+        assert_eq!(rdata.to_string(), "0 issue \"letsencrypt.org\"");
+    }
+}
+
+fn parse_dns_rdata_opt(
+    message: &Vec<u8>,
+    offset: usize,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    let mut options = Vec::new();
+    let mut option_offset = offset;
+    while option_offset < offset + rd_length {
+        let code = parse_u16(message, option_offset)?;
+        let option_length = parse_u16(message, option_offset + 2)? as usize;
+        if option_offset + 4 + option_length > offset + rd_length {
+            return Err(DnsParseError::BadRdata(option_offset));
+        }
+        let data = get_bytes(message, option_offset + 4, option_length)?.to_vec();
+
+        options.push(EdnsOption { code, data });
+        option_offset += 4 + option_length;
+    }
+
+    return Ok(RData::OPT(options));
+}
+#[cfg(test)]
+mod parse_dns_rdata_opt {
+    use crate::dns::{
+        error::DnsParseError,
+        model::{EdnsOption, RData},
+        parser::parse_dns_rdata_opt,
+    };
+
+    #[test]
+    fn case1() {
+        let rdata = parse_dns_rdata_opt(
+            &vec![
+                0x00, 0x0a, // code=COOKIE
+                0x00, 0x08, // length
+                0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // client cookie
+                0x00, 0x0c, // code=PADDING
+                0x00, 0x00, // length
+            ],
+            0,
+            16,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rdata,
+            RData::OPT(vec![
+                EdnsOption {
+                    code: 10,
+                    data: vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]
+                },
+                EdnsOption {
+                    code: 12,
+                    data: vec![]
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn case2() {
+        let error = parse_dns_rdata_opt(
+            &vec![
+                0x00, 0x0a, // code=COOKIE
+                0x00, 0x08, // length overruns rdata
+                0x01, 0x02, 0x03, 0x04,
+            ],
+            0,
+            8,
+        )
+        .unwrap_err();
+
+        assert_eq!(error, DnsParseError::BadRdata(0));
+    }
+}
+
+// see: https://www.rfc-editor.org/rfc/rfc4034#section-4.1.2
+fn parse_type_bitmap(
+    message: &Vec<u8>,
+    offset: usize,
+    length: usize,
+) -> Result<Vec<DnsType>, DnsParseError> {
+    let mut types = Vec::new();
+    let mut window_offset = offset;
+    while window_offset < offset + length {
+        let window = parse_u8(message, window_offset)? as u16;
+        let bitmap_length = parse_u8(message, window_offset + 1)? as usize;
+        if bitmap_length == 0
+            || bitmap_length > 32
+            || window_offset + 2 + bitmap_length > offset + length
+        {
+            return Err(DnsParseError::BadRdata(window_offset));
+        }
+        let bitmap = get_bytes(message, window_offset + 2, bitmap_length)?;
+
+        for (index, &byte) in bitmap.iter().enumerate() {
+            for bit in 0..8 {
+                if byte & (0b10000000 >> bit) != 0 {
+                    types.push(DnsType::from(window << 8 | (index * 8 + bit) as u16));
+                }
+            }
+        }
+        window_offset += 2 + bitmap_length;
+    }
+
+    return Ok(types);
+}
+#[cfg(test)]
+mod parse_type_bitmap {
+    use crate::dns::{error::DnsParseError, model::DnsType, parser::parse_type_bitmap};
+
+    #[test]
+    fn case1() {
+        let types = parse_type_bitmap(
+            &vec![
+                0x00, 0x06, 0x40, 0x01, 0x00, 0x00, 0x00, 0x03, // window 0
+                0x04, 0x1b, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x20, // window 4
+            ],
+            0,
+            37,
+        )
+        .unwrap();
+
+        assert_eq!(
+            types,
+            vec![
+                DnsType::A,
+                DnsType::MX,
+                DnsType::RRSIG,
+                DnsType::NSEC,
+                DnsType::Unknown(1234)
+            ]
+        );
+    }
+
+    #[test]
+    fn case2() {
+        let error = parse_type_bitmap(&vec![0x00, 0x00], 0, 2).unwrap_err();
+        assert_eq!(error, DnsParseError::BadRdata(0));
+    }
+}
+
+fn parse_dns_rdata_ds(
     message: &Vec<u8>,
     offset: usize,
-) -> Result<(DnsResourceRecord, usize), (String, usize)> {
-    let (name, name_length) = print_dns_domain_name(message, offset);
-
-    let rd_length = u16::from_be_bytes([
-        message[offset + name_length + 8],
-        message[offset + name_length + 9],
-    ]) as usize;
-
-    let rr_type = DnsType::from_u16(u16::from_be_bytes([
-        message[offset + name_length],
-        message[offset + name_length + 1],
-    ]))
-    .ok_or(("unknown rr_type".to_string(), name_length + 10 + rd_length))?;
-    let rr_class = DnsClass::from_u16(u16::from_be_bytes([
-        message[offset + name_length + 2],
-        message[offset + name_length + 3],
-    ]))
-    .ok_or(("unknown rr_class".to_string(), name_length + 10 + rd_length))?;
-    let ttl = u32::from_be_bytes([
-        message[offset + name_length + 4],
-        message[offset + name_length + 5],
-        message[offset + name_length + 6],
-        message[offset + name_length + 7],
-    ]);
-
-    let rdata = match rr_type {
-        DnsType::A => print_ipaddr(&vec![
-            message[offset + name_length + 10],
-            message[offset + name_length + 11],
-            message[offset + name_length + 12],
-            message[offset + name_length + 13],
-        ]),
-        DnsType::NS => print_dns_domain_name(message, offset + name_length + 10).0,
-        DnsType::CNAME => print_dns_domain_name(message, offset + name_length + 10).0,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    if rd_length < 4 {
+        return Err(DnsParseError::BadRdata(offset));
+    }
+    let key_tag = parse_u16(message, offset)?;
+    let algorithm = parse_u8(message, offset + 2)?;
+    let digest_type = parse_u8(message, offset + 3)?;
+    let digest = get_bytes(message, offset + 4, rd_length - 4)?.to_vec();
+
+    return Ok(RData::DS {
+        key_tag,
+        algorithm,
+        digest_type,
+        digest,
+    });
+}
+#[cfg(test)]
+mod parse_dns_rdata_ds {
+    use crate::dns::parser::parse_dns_rdata_ds;
+
+    #[test]
+    fn case1() {
+        // from rfc4034 5.4
+        let rdata = parse_dns_rdata_ds(
+            &vec![
+                0xec, 0x45, // key tag
+                0x05, // algorithm
+                0x01, // digest type
+                0x2b, 0xb1, 0x83, 0xaf, 0x5f, 0x22, 0x58, 0x81, 0x79, 0xa5, 0x3b, 0x0a, 0x98, 0x63,
+                0x1f, 0xad, 0x1a, 0x29, 0x21, 0x18, // digest
+            ],
+            0,
+            24,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rdata.to_string(),
+            "60485 5 1 2BB183AF5F22588179A53B0A98631FAD1A292118"
+        );
+    }
+}
+
+fn parse_dns_rdata_rrsig(
+    message: &Vec<u8>,
+    offset: usize,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    let type_covered = DnsType::from(parse_u16(message, offset)?);
+    let algorithm = parse_u8(message, offset + 2)?;
+    let labels = parse_u8(message, offset + 3)?;
+    let original_ttl = parse_u32(message, offset + 4)?;
+    let expiration = parse_u32(message, offset + 8)?;
+    let inception = parse_u32(message, offset + 12)?;
+    let key_tag = parse_u16(message, offset + 16)?;
+    let (signer_name, signer_name_length) = print_dns_domain_name(message, offset + 18)?;
+    if 18 + signer_name_length > rd_length {
+        return Err(DnsParseError::BadRdata(offset));
+    }
+    let signature = get_bytes(
+        message,
+        offset + 18 + signer_name_length,
+        rd_length - 18 - signer_name_length,
+    )?
+    .to_vec();
+
+    return Ok(RData::RRSIG {
+        type_covered,
+        algorithm,
+        labels,
+        original_ttl,
+        expiration,
+        inception,
+        key_tag,
+        signer_name,
+        signature,
+    });
+}
+#[cfg(test)]
+mod parse_dns_rdata_rrsig {
+    use crate::dns::parser::parse_dns_rdata_rrsig;
+
+    #[test]
+    fn case1() {
+        // from rfc4034 3.3, with a shortened signature
+        let rdata = parse_dns_rdata_rrsig(
+            &vec![
+                0x00, 0x01, // type covered
+                0x05, // algorithm
+                0x03, // labels
+                0x00, 0x01, 0x51, 0x80, // original ttl
+                0x3e, 0x7c, 0x9d, 0xd7, // signature expiration
+                0x3e, 0x55, 0x10, 0xd7, // signature inception
+                0x0a, 0x52, // key tag
+                7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0, // signer
+                0xa0, 0x90, 0x75, 0x5b, // signature
+            ],
+            0,
+            35,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rdata.to_string(),
+            "A 5 3 86400 20030322173103 20030220173103 2642 example.com. oJB1Ww=="
+        );
+    }
+}
+
+fn parse_dns_rdata_nsec(
+    message: &Vec<u8>,
+    offset: usize,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    let (next_domain_name, next_domain_name_length) = print_dns_domain_name(message, offset)?;
+    if next_domain_name_length > rd_length {
+        return Err(DnsParseError::BadRdata(offset));
+    }
+    let types = parse_type_bitmap(
+        message,
+        offset + next_domain_name_length,
+        rd_length - next_domain_name_length,
+    )?;
+
+    return Ok(RData::NSEC {
+        next_domain_name,
+        types,
+    });
+}
+#[cfg(test)]
+mod parse_dns_rdata_nsec {
+    use crate::dns::parser::parse_dns_rdata_nsec;
+
+    #[test]
+    fn case1() {
+        // from rfc4034 4.3
+        let mut message = vec![
+            4, b'h', b'o', b's', b't', 7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o',
+            b'm', 0, // next domain name
+            0x00, 0x06, 0x40, 0x01, 0x00, 0x00, 0x00, 0x03, // window 0
+            0x04, 0x1b, // window 4
+        ];
+        message.extend_from_slice(&[0; 26]);
+        message.push(0x20);
+
+        let rdata = parse_dns_rdata_nsec(&message, 0, message.len()).unwrap();
+
+        assert_eq!(
+            rdata.to_string(),
+            "host.example.com. A MX RRSIG NSEC TYPE1234"
+        );
+    }
+}
+
+fn parse_dns_rdata_dnskey(
+    message: &Vec<u8>,
+    offset: usize,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    if rd_length < 4 {
+        return Err(DnsParseError::BadRdata(offset));
+    }
+    let flags = parse_u16(message, offset)?;
+    let protocol = parse_u8(message, offset + 2)?;
+    let algorithm = parse_u8(message, offset + 3)?;
+    let public_key = get_bytes(message, offset + 4, rd_length - 4)?.to_vec();
+
+    return Ok(RData::DNSKEY {
+        flags,
+        protocol,
+        algorithm,
+        public_key,
+    });
+}
+#[cfg(test)]
+mod parse_dns_rdata_dnskey {
+    use crate::dns::parser::parse_dns_rdata_dnskey;
+
+    #[test]
+    fn case1() {
+        let rdata = parse_dns_rdata_dnskey(
+            &vec![
+                0x01, 0x01, // flags
+                0x03, // protocol
+                0x0f, // algorithm
+                b'f', b'o', b'o', b'b', b'a', b'r', // public key
+            ],
+            0,
+            10,
+        )
+        .unwrap();
+
+        assert_eq!(rdata.to_string(), "257 3 15 Zm9vYmFy");
+    }
+}
+
+fn parse_dns_rdata_nsec3(
+    message: &Vec<u8>,
+    offset: usize,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    let hash_algorithm = parse_u8(message, offset)?;
+    let flags = parse_u8(message, offset + 1)?;
+    let iterations = parse_u16(message, offset + 2)?;
+    let salt_length = parse_u8(message, offset + 4)? as usize;
+    let salt = get_bytes(message, offset + 5, salt_length)?.to_vec();
+    let hash_length = parse_u8(message, offset + 5 + salt_length)? as usize;
+    let next_hashed_owner_name =
+        get_bytes(message, offset + 6 + salt_length, hash_length)?.to_vec();
+    let length = 6 + salt_length + hash_length;
+    if length > rd_length {
+        return Err(DnsParseError::BadRdata(offset));
+    }
+    let types = parse_type_bitmap(message, offset + length, rd_length - length)?;
+
+    return Ok(RData::NSEC3 {
+        hash_algorithm,
+        flags,
+        iterations,
+        salt,
+        next_hashed_owner_name,
+        types,
+    });
+}
+#[cfg(test)]
+mod parse_dns_rdata_nsec3 {
+    use crate::dns::{error::DnsParseError, parser::parse_dns_rdata_nsec3};
+
+    #[test]
+    fn case1() {
+        let rdata = parse_dns_rdata_nsec3(
+            &vec![
+                0x01, // hash algorithm
+                0x01, // flags
+                0x00, 0x0c, // iterations
+                0x04, 0xaa, 0xbb, 0xcc, 0xdd, // salt
+                0x06, b'f', b'o', b'o', b'b', b'a', b'r', // next hashed owner name
+                0x00, 0x01, 0x40, // window 0
+            ],
+            0,
+            19,
+        )
+        .unwrap();
+
+        assert_eq!(rdata.to_string(), "1 1 12 AABBCCDD CPNMUOJ1E8 A");
+    }
+
+    #[test]
+    fn case2() {
+        let error = parse_dns_rdata_nsec3(
+            &vec![
+                0x01, // hash algorithm
+                0x00, // flags
+                0x00, 0x00, // iterations
+                0x00, // salt
+                0x06, b'f', b'o', b'o', // next hashed owner name overruns rdata
+            ],
+            0,
+            9,
+        )
+        .unwrap_err();
+
+        assert_eq!(error, DnsParseError::Truncated(6));
+    }
+}
+
+fn parse_dns_rdata_nsec3param(
+    message: &Vec<u8>,
+    offset: usize,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    let hash_algorithm = parse_u8(message, offset)?;
+    let flags = parse_u8(message, offset + 1)?;
+    let iterations = parse_u16(message, offset + 2)?;
+    let salt_length = parse_u8(message, offset + 4)? as usize;
+    check_rdata_length(offset, 5 + salt_length, rd_length)?;
+    let salt = get_bytes(message, offset + 5, salt_length)?.to_vec();
+
+    return Ok(RData::NSEC3PARAM {
+        hash_algorithm,
+        flags,
+        iterations,
+        salt,
+    });
+}
+#[cfg(test)]
+mod parse_dns_rdata_nsec3param {
+    use crate::dns::parser::parse_dns_rdata_nsec3param;
+
+    #[test]
+    fn case1() {
+        let rdata = parse_dns_rdata_nsec3param(
+            &vec![
+                0x01, // hash algorithm
+                0x00, // flags
+                0x00, 0x00, // iterations
+                0x00, // salt
+            ],
+            0,
+            5,
+        )
+        .unwrap();
+
+        assert_eq!(rdata.to_string(), "1 0 0 -");
+    }
+}
+
+fn parse_dns_rdata_name(
+    message: &Vec<u8>,
+    offset: usize,
+    rd_length: usize,
+) -> Result<DomainName, DnsParseError> {
+    let (name, name_length) = print_dns_domain_name(message, offset)?;
+    check_rdata_length(offset, name_length, rd_length)?;
+
+    return Ok(name);
+}
+
+pub(super) fn parse_dns_rdata(
+    message: &Vec<u8>,
+    offset: usize,
+    rr_type: &DnsType,
+    rd_length: usize,
+) -> Result<RData, DnsParseError> {
+    return match rr_type {
+        DnsType::A => parse_dns_rdata_a(message, offset, rd_length),
+        DnsType::NS => Ok(RData::NS(parse_dns_rdata_name(message, offset, rd_length)?)),
+        DnsType::CNAME => Ok(RData::CNAME(parse_dns_rdata_name(
+            message, offset, rd_length,
+        )?)),
+        DnsType::SOA => parse_dns_rdata_soa(message, offset, rd_length),
+        DnsType::PTR => Ok(RData::PTR(parse_dns_rdata_name(
+            message, offset, rd_length,
+        )?)),
+        DnsType::MX => parse_dns_rdata_mx(message, offset, rd_length),
+        DnsType::TXT => parse_dns_rdata_txt(message, offset, rd_length),
+        DnsType::AAAA => parse_dns_rdata_aaaa(message, offset, rd_length),
+        DnsType::SRV => parse_dns_rdata_srv(message, offset, rd_length),
+        DnsType::CAA => parse_dns_rdata_caa(message, offset, rd_length),
+        DnsType::OPT => parse_dns_rdata_opt(message, offset, rd_length),
+        DnsType::DS => parse_dns_rdata_ds(message, offset, rd_length),
+        DnsType::RRSIG => parse_dns_rdata_rrsig(message, offset, rd_length),
+        DnsType::NSEC => parse_dns_rdata_nsec(message, offset, rd_length),
+        DnsType::DNSKEY => parse_dns_rdata_dnskey(message, offset, rd_length),
+        DnsType::NSEC3 => parse_dns_rdata_nsec3(message, offset, rd_length),
+        DnsType::NSEC3PARAM => parse_dns_rdata_nsec3param(message, offset, rd_length),
+        // ANY is only meaningful in questions, keep whatever comes as is
+        DnsType::ANY | DnsType::Unknown(_) => Ok(RData::Unknown(
+            get_bytes(message, offset, rd_length)?.to_vec(),
+        )),
     };
+}
+
+fn parse_dns_resource_record(
+    message: &Vec<u8>,
+    offset: usize,
+) -> Result<(DnsResourceRecord, usize), DnsParseError> {
+    let (name, name_length) = print_dns_domain_name(message, offset)?;
+
+    let rr_type = DnsType::from(parse_u16(message, offset + name_length)?);
+    let rr_class = DnsClass::from(parse_u16(message, offset + name_length + 2)?);
+    let ttl = parse_u32(message, offset + name_length + 4)?;
+    let rd_length = parse_u16(message, offset + name_length + 8)? as usize;
+
+    // the whole rdata must be present before looking into it
+    get_bytes(message, offset + name_length + 10, rd_length)?;
+    let rdata = parse_dns_rdata(message, offset + name_length + 10, &rr_type, rd_length)?;
 
     return Ok((
         DnsResourceRecord {
@@ -274,7 +1219,7 @@ fn parse_dns_resource_record(
 #[cfg(test)]
 mod parse_dns_resource_record {
     use crate::dns::{
-        model::{DnsClass, DnsType},
+        model::{DnsClass, DnsType, RData},
         parser::parse_dns_resource_record,
     };
 
@@ -294,11 +1239,11 @@ mod parse_dns_resource_record {
         )
         .unwrap();
 
-        assert_eq!(rr.name, "example.com.");
+        assert_eq!(rr.name.to_string(), "example.com.");
         assert_eq!(rr.rr_type, DnsType::A);
         assert_eq!(rr.rr_class, DnsClass::IN);
         assert_eq!(rr.ttl, 123);
-        assert_eq!(rr.rdata, "8.8.8.8");
+        assert_eq!(rr.rdata, RData::A("8.8.8.8".parse().unwrap()));
         assert_eq!(size, 27);
     }
 
@@ -318,11 +1263,11 @@ mod parse_dns_resource_record {
         )
         .unwrap();
 
-        assert_eq!(rr.name, "example.com.");
+        assert_eq!(rr.name.to_string(), "example.com.");
         assert_eq!(rr.rr_type, DnsType::NS);
         assert_eq!(rr.rr_class, DnsClass::IN);
         assert_eq!(rr.ttl, 123);
-        assert_eq!(rr.rdata, "example.com.");
+        assert_eq!(rr.rdata, RData::NS("example.com.".parse().unwrap()));
         assert_eq!(size, 36);
     }
 
@@ -342,94 +1287,154 @@ mod parse_dns_resource_record {
         )
         .unwrap();
 
-        assert_eq!(rr.name, "example.com.");
+        assert_eq!(rr.name.to_string(), "example.com.");
         assert_eq!(rr.rr_type, DnsType::CNAME);
         assert_eq!(rr.rr_class, DnsClass::IN);
         assert_eq!(rr.ttl, 123);
-        assert_eq!(rr.rdata, "example.com.");
+        assert_eq!(rr.rdata, RData::CNAME("example.com.".parse().unwrap()));
         assert_eq!(size, 36);
     }
 
     #[test]
     fn case4() {
-        let (name, size) = parse_dns_resource_record(
+        let (rr, size) = parse_dns_resource_record(
             &vec![
                 0, 0, 0, 0, // offset
                 7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0, // name
                 0x00, 0x01, // rr type
-                0x00, 0xff, // unknown rr class!
+                0x00, 0x63, // unknown rr class!
                 0x00, 0x00, 0x00, 123, // ttl
                 0x00, 0x04, // rd_length
                 0x08, 0x08, 0x08, 0x08, // rdata
             ],
             4,
         )
-        .unwrap_err();
+        .unwrap();
+
+        assert_eq!(rr.rr_type, DnsType::A);
+        assert_eq!(rr.rr_class, DnsClass::Unknown(99));
+        assert_eq!(rr.to_string(), "example.com.\t123\tCLASS99\tA\t8.8.8.8");
+        assert_eq!(size, 27);
+    }
+
+    #[test]
+    fn case5() {
+        let (rr, size) = parse_dns_resource_record(
+            &vec![
+                0, 0, 0, 0, // offset
+                7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0, // name
+                0x30, 0x39, // unknown rr type!
+                0x00, 0x01, // rr class
+                0x00, 0x00, 0x00, 123, // ttl
+                0x00, 0x04, // rd_length
+                0x0a, 0x00, 0x00, 0x01, // rdata
+            ],
+            4,
+        )
+        .unwrap();
 
-        assert_eq!(name, "unknown rr_class".to_string());
+        assert_eq!(rr.rr_type, DnsType::Unknown(12345));
+        assert_eq!(rr.rdata, RData::Unknown(vec![0x0a, 0x00, 0x00, 0x01]));
+        assert_eq!(
+            rr.to_string(),
+            "example.com.\t123\tIN\tTYPE12345\t\\# 4 0A000001"
+        );
         assert_eq!(size, 27);
     }
 }
 
-pub fn parse_dns_message(message: &Vec<u8>) -> Result<DnsMessage, String> {
+fn parse_dns_resource_records(
+    message: &Vec<u8>,
+    offset: usize,
+    count: u16,
+) -> Result<(Vec<DnsResourceRecord>, usize), DnsParseError> {
+    let mut rrs = Vec::new();
+    let mut rr_offset = offset;
+    for _ in 0..count {
+        // the header promised more records than the message holds
+        if rr_offset >= message.len() {
+            return Err(DnsParseError::CountMismatch(rr_offset));
+        }
+
+        let (rr, size) = parse_dns_resource_record(message, rr_offset)?;
+        rrs.push(rr);
+        rr_offset += size;
+    }
+
+    return Ok((rrs, rr_offset - offset));
+}
+
+// see: https://www.rfc-editor.org/rfc/rfc6891#section-6.1.3
+pub(super) fn parse_dns_edns(rr: DnsResourceRecord) -> Edns {
+    let options = match rr.rdata {
+        RData::OPT(options) => options,
+        _ => Vec::new(),
+    };
+
+    return Edns {
+        udp_payload_size: rr.rr_class.into(),
+        extended_rcode: (rr.ttl >> 24) as u8,
+        version: (rr.ttl >> 16) as u8,
+        dnssec_ok: rr.ttl & 0x00008000 != 0,
+        options,
+    };
+}
+
+/// Parses a whole message. The OPT pseudo-record is moved to [`DnsMessage::edns`].
+///
+/// ```
+/// use rust_dns::dns::*;
+///
+/// let bytes = vec![
+///     0x12, 0x34, 0x81, 0x80, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, // header
+///     0x01, b'a', 0x00, 0x00, 0x01, 0x00, 0x01, // a. IN A
+///     0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x04, 192, 0, 2, 1,
+/// ];
+/// let message = parse_dns_message(&bytes).unwrap();
+/// assert_eq!(message.answers[0].to_string(), "a.\t3600\tIN\tA\t192.0.2.1");
+///
+/// assert!(parse_dns_message(&bytes[..20].to_vec()).is_err());
+/// ```
+pub fn parse_dns_message(message: &Vec<u8>) -> Result<DnsMessage, DnsParseError> {
     let header = parse_dns_header_section(message)?;
 
     let mut offset = 12; // header length
 
     let mut questions = Vec::new();
     for _ in 0..header.qdcount {
-        let result = parse_dns_question_section_entry(message, offset);
-        match result {
-            Ok((question, size)) => {
-                questions.push(question);
-                offset += size;
-            }
-            Err((_, size)) => {
-                offset += size;
-            }
+        if offset >= message.len() {
+            return Err(DnsParseError::CountMismatch(offset));
         }
-    }
 
-    let mut answers = Vec::new();
-    for _ in 0..header.ancount {
-        let result = parse_dns_resource_record(message, offset);
-        match result {
-            Ok((rr, size)) => {
-                answers.push(rr);
-                offset += size;
-            }
-            Err((_, size)) => {
-                offset += size;
-            }
-        }
+        let (question, size) = parse_dns_question_section_entry(message, offset)?;
+        questions.push(question);
+        offset += size;
     }
 
-    let mut authorities = Vec::new();
-    for _ in 0..header.nscount {
-        let result = parse_dns_resource_record(message, offset);
-        match result {
-            Ok((rr, size)) => {
-                authorities.push(rr);
-                offset += size;
-            }
-            Err((_, size)) => {
-                offset += size;
-            }
-        }
-    }
+    let (answers, size) = parse_dns_resource_records(message, offset, header.ancount)?;
+    offset += size;
+
+    let (authorities, size) = parse_dns_resource_records(message, offset, header.nscount)?;
+    offset += size;
 
     let mut additionals = Vec::new();
+    let mut edns = None;
     for _ in 0..header.arcount {
-        let result = parse_dns_resource_record(message, offset);
-        match result {
-            Ok((rr, size)) => {
-                additionals.push(rr);
-                offset += size;
-            }
-            Err((_, size)) => {
-                offset += size;
+        if offset >= message.len() {
+            return Err(DnsParseError::CountMismatch(offset));
+        }
+
+        let (rr, size) = parse_dns_resource_record(message, offset)?;
+        if rr.rr_type == DnsType::OPT {
+            // at most one OPT record owned by the root is allowed
+            if edns.is_some() || !rr.name.is_root() {
+                return Err(DnsParseError::BadOpt(offset));
             }
+            edns = Some(parse_dns_edns(rr));
+        } else {
+            additionals.push(rr);
         }
+        offset += size;
     }
 
     return Ok(DnsMessage {
@@ -438,12 +1443,15 @@ pub fn parse_dns_message(message: &Vec<u8>) -> Result<DnsMessage, String> {
         answers,
         authorities,
         additionals,
+        edns,
     });
 }
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod parse_dns_message {
     use crate::dns::{
-        model::{DnsClass, DnsHeaderOpecode, DnsHeaderRcode, DnsType},
+        error::DnsParseError,
+        model::{DnsClass, DnsHeaderOpecode, DnsHeaderRcode, DnsType, Edns, RData},
         parser::parse_dns_message,
     };
 
@@ -525,24 +1533,137 @@ mod parse_dns_message {
         assert_eq!(message.header.arcount, 10);
 
         assert_eq!(message.questions.len(), 1);
-        assert_eq!(message.questions[0].q_name, "a1422.dscr.akamai.net.");
+        assert_eq!(
+            message.questions[0].q_name.to_string(),
+            "a1422.dscr.akamai.net."
+        );
         assert_eq!(message.questions[0].q_type, DnsType::A);
         assert_eq!(message.questions[0].q_class, DnsClass::IN);
 
         assert_eq!(message.answers.len(), 0);
 
         assert_eq!(message.authorities.len(), 8);
-        assert_eq!(message.authorities[0].name, "dscr.akamai.net.");
+        assert_eq!(message.authorities[0].name.to_string(), "dscr.akamai.net.");
         assert_eq!(message.authorities[0].rr_type, DnsType::NS);
         assert_eq!(message.authorities[0].rr_class, DnsClass::IN);
         assert_eq!(message.authorities[0].ttl, 4000);
-        assert_eq!(message.authorities[0].rdata, "n7dscr.akamai.net.");
+        assert_eq!(
+            message.authorities[0].rdata,
+            RData::NS("n7dscr.akamai.net.".parse().unwrap())
+        );
 
-        assert_eq!(message.additionals.len(), 8);
-        assert_eq!(message.additionals[0].name, "n4dscr.akamai.net.");
+        assert_eq!(message.additionals.len(), 9);
+        assert_eq!(
+            message.additionals[0].name.to_string(),
+            "n4dscr.akamai.net."
+        );
         assert_eq!(message.additionals[0].rr_type, DnsType::A);
         assert_eq!(message.additionals[0].rr_class, DnsClass::IN);
         assert_eq!(message.additionals[0].ttl, 4000);
-        assert_eq!(message.additionals[0].rdata, "23.204.120.92");
+        assert_eq!(
+            message.additionals[0].rdata,
+            RData::A("23.204.120.92".parse().unwrap())
+        );
+        assert_eq!(
+            message.additionals[1].name.to_string(),
+            "n0dscr.akamai.net."
+        );
+        assert_eq!(message.additionals[1].rr_type, DnsType::AAAA);
+        assert_eq!(
+            message.additionals[1].rdata,
+            RData::AAAA("2600:1480:e800::c0".parse().unwrap())
+        );
+
+        assert_eq!(
+            message.edns,
+            Some(Edns {
+                udp_payload_size: 4096,
+                extended_rcode: 0,
+                version: 0,
+                dnssec_ok: false,
+                options: vec![]
+            })
+        );
+
+        assert_eq!(
+            parse_dns_message(&message.to_wire().unwrap()).unwrap(),
+            message
+        );
+    }
+
+    #[test]
+    fn case2() {
+        let error = parse_dns_message(&vec![
+            0x00, 0x01, // id
+            0x80, // qr opecode=Query !aa !tc !rd
+            0x00, // !ra ZZZ rcode=NoError
+            0x00, 0x01, // qdcount=1
+            0x00, 0x01, // ancount=1 (missing!)
+            0x00, 0x00, // nscount=0
+            0x00, 0x00, // arcount=0
+            // questions
+            0x03, b'c', b'o', b'm', 0x00, 0x00, 0x01, 0x00, 0x01, // com. IN A
+        ])
+        .unwrap_err();
+
+        assert_eq!(error, DnsParseError::CountMismatch(21));
+    }
+
+    #[test]
+    fn case3() {
+        let error = parse_dns_message(&vec![
+            0x00, 0x01, // id
+            0x80, // qr opecode=Query !aa !tc !rd
+            0x00, // !ra ZZZ rcode=NoError
+            0x00, 0x00, // qdcount=0
+            0x00, 0x01, // ancount=1
+            0x00, 0x00, // nscount=0
+            0x00, 0x00, // arcount=0
+            // answers
+            0x03, b'c', b'o', b'm', 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x10, 0x00,
+            0x04, 0x08, 0x08, // com. 16 IN A 8.8. (truncated!)
+        ])
+        .unwrap_err();
+
+        assert_eq!(error, DnsParseError::Truncated(27));
+    }
+
+    #[test]
+    fn case4() {
+        let error = parse_dns_message(&vec![
+            0x00, 0x01, // id
+            0x80, // qr opecode=Query !aa !tc !rd
+            0x00, // !ra ZZZ rcode=NoError
+            0x00, 0x00, // qdcount=0
+            0x00, 0x00, // ancount=0
+            0x00, 0x00, // nscount=0
+            0x00, 0x02, // arcount=2
+            // additionals
+            0x00, 0x00, 0x29, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // ROOT OPT
+            0x00, 0x00, 0x29, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, // ROOT OPT again!
+        ])
+        .unwrap_err();
+
+        assert_eq!(error, DnsParseError::BadOpt(23));
+    }
+
+    #[test]
+    fn case5() {
+        let message = parse_dns_message(&vec![
+            0x00, 0x01, // id
+            0x80, // qr opecode=Query !aa !tc !rd
+            0x00, // !ra ZZZ rcode=NoError
+            0x00, 0x00, // qdcount=0
+            0x00, 0x00, // ancount=0
+            0x00, 0x00, // nscount=0
+            0x00, 0x01, // arcount=1
+            // additionals
+            0x00, 0x00, 0x29, 0x10, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, // ROOT OPT
+        ])
+        .unwrap();
+
+        assert_eq!(message.header.rcode, DnsHeaderRcode::NoError);
+        assert_eq!(message.rcode(), DnsHeaderRcode::BadVers);
     }
 }
@@ -0,0 +1,375 @@
+#![allow(clippy::needless_return)]
+
+use std::str::FromStr;
+
+use super::{
+    encoding::print_hex,
+    json::print_json_message,
+    model::{DnsHeaderOpecode, DnsHeaderRcode, DnsMessage, DnsResourceRecord, Edns},
+};
+
+/// How a whole message is shown by [`print_message`].
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum MessageFormat {
+    // Display of DnsMessage
+    Default,
+    // the layout of dig, to diff against it
+    Dig,
+    // only the records, as a zone master file
+    Zone,
+    // rfc8427 on a single line
+    Json,
+}
+impl FromStr for MessageFormat {
+    type Err = String;
+
+    fn from_str(format_str: &str) -> Result<Self, Self::Err> {
+        return match format_str {
+            "default" => Ok(MessageFormat::Default),
+            "dig" => Ok(MessageFormat::Dig),
+            "zone" => Ok(MessageFormat::Zone),
+            "json" => Ok(MessageFormat::Json),
+            _ => Err(format!("unknown format: {}", format_str)),
+        };
+    }
+}
+
+// only the JSON encoding can fail
+pub fn print_message(message: &DnsMessage, format: MessageFormat) -> Result<String, String> {
+    return match format {
+        MessageFormat::Default => Ok(message.to_string()),
+        MessageFormat::Dig => Ok(print_dig_message(message)),
+        MessageFormat::Zone => Ok(print_zone(
+            &[
+                message.answers.as_slice(),
+                message.authorities.as_slice(),
+                message.additionals.as_slice(),
+            ]
+            .concat(),
+        )),
+        MessageFormat::Json => print_json_message(message),
+    };
+}
+
+// mnemonics as dig prints them
+fn print_dig_opcode(opcode: DnsHeaderOpecode) -> String {
+    return match opcode {
+        DnsHeaderOpecode::Query => "QUERY".to_string(),
+        DnsHeaderOpecode::IQuery => "IQUERY".to_string(),
+        DnsHeaderOpecode::Status => "STATUS".to_string(),
+        DnsHeaderOpecode::Notify => "NOTIFY".to_string(),
+        DnsHeaderOpecode::Update => "UPDATE".to_string(),
+        DnsHeaderOpecode::DSO => "DSO".to_string(),
+        DnsHeaderOpecode::Unknown(value) => format!("RESERVED{}", value),
+    };
+}
+
+fn print_dig_rcode(rcode: DnsHeaderRcode) -> String {
+    return match rcode {
+        DnsHeaderRcode::NoError => "NOERROR".to_string(),
+        DnsHeaderRcode::FormatError => "FORMERR".to_string(),
+        DnsHeaderRcode::ServerFailure => "SERVFAIL".to_string(),
+        DnsHeaderRcode::NoNameError => "NXDOMAIN".to_string(),
+        DnsHeaderRcode::NotImplemented => "NOTIMP".to_string(),
+        DnsHeaderRcode::Refused => "REFUSED".to_string(),
+        DnsHeaderRcode::YXDomain => "YXDOMAIN".to_string(),
+        DnsHeaderRcode::YXRRSet => "YXRRSET".to_string(),
+        DnsHeaderRcode::NXRRSet => "NXRRSET".to_string(),
+        DnsHeaderRcode::NotAuth => "NOTAUTH".to_string(),
+        DnsHeaderRcode::NotZone => "NOTZONE".to_string(),
+        DnsHeaderRcode::DSOTypeNI => "DSOTYPENI".to_string(),
+        DnsHeaderRcode::BadVers => "BADVERS".to_string(),
+        DnsHeaderRcode::BadKey => "BADKEY".to_string(),
+        DnsHeaderRcode::BadTime => "BADTIME".to_string(),
+        DnsHeaderRcode::BadMode => "BADMODE".to_string(),
+        DnsHeaderRcode::BadName => "BADNAME".to_string(),
+        DnsHeaderRcode::BadAlg => "BADALG".to_string(),
+        DnsHeaderRcode::BadTrunc => "BADTRUNC".to_string(),
+        DnsHeaderRcode::BadCookie => "BADCOOKIE".to_string(),
+        DnsHeaderRcode::Unknown(value) => format!("RESERVED{}", value),
+    };
+}
+
+// dig aligns fields with tabs, the ttl to column 24, the class to 32, the type to 40 and rdata to 48
+fn print_columns(fields: &[(&str, usize)], last: &str) -> String {
+    let mut line = String::new();
+    let mut width = 0;
+    for (field, column) in fields {
+        line.push_str(field);
+        width += field.len();
+        // at least one tab, each moves to the next multiple of 8
+        loop {
+            line.push('\t');
+            width = (width / 8 + 1) * 8;
+            if width >= *column {
+                break;
+            }
+        }
+    }
+    line.push_str(last);
+
+    return line;
+}
+#[cfg(test)]
+mod print_columns {
+    use crate::dns::presentation::print_columns;
+
+    #[test]
+    fn case1() {
+        assert_eq!(
+            print_columns(
+                &[("example.com.", 24), ("86400", 32), ("IN", 40), ("A", 48)],
+                "192.0.2.1"
+            ),
+            "example.com.\t\t86400\tIN\tA\t192.0.2.1"
+        );
+        assert_eq!(
+            print_columns(&[(";example.com.", 32), ("IN", 40)], "A"),
+            ";example.com.\t\t\tIN\tA"
+        );
+        assert_eq!(
+            print_columns(&[("a-very-long-name.example.com.", 24), ("0", 32)], "x"),
+            "a-very-long-name.example.com.\t0\tx"
+        );
+    }
+}
+
+fn print_dig_record(record: &DnsResourceRecord) -> String {
+    return print_columns(
+        &[
+            (&record.name.to_string(), 24),
+            (&record.ttl.to_string(), 32),
+            (&record.rr_class.to_string(), 40),
+            (&record.rr_type.to_string(), 48),
+        ],
+        &record.rdata.to_string(),
+    );
+}
+
+// see: https://www.rfc-editor.org/rfc/rfc6891#section-6.1.3
+fn print_dig_edns(edns: &Edns) -> String {
+    let mut string = format!(
+        "; EDNS: version: {}, flags:{}; udp: {}\n",
+        edns.version,
+        if edns.dnssec_ok { " do" } else { "" },
+        edns.udp_payload_size
+    );
+    for option in &edns.options {
+        match option.code {
+            // see: https://www.rfc-editor.org/rfc/rfc7873#section-4
+            10 => string.push_str(&format!(
+                "; COOKIE: {}\n",
+                print_hex(&option.data).to_lowercase()
+            )),
+            code => string.push_str(&format!("; OPT={}: {}\n", code, print_hex(&option.data))),
+        }
+    }
+
+    return string;
+}
+
+/// The sections of dig output, without the query time and server footer.
+///
+/// ```
+/// use rust_dns::dns::*;
+///
+/// let query = QueryBuilder::new(1)
+///     .question(&"example.com.".parse().unwrap(), DnsType::A, DnsClass::IN)
+///     .build();
+/// assert!(print_dig_message(&query).starts_with(";; ->>HEADER<<- opcode: QUERY, status: NOERROR, id: 1\n"));
+/// ```
+pub fn print_dig_message(message: &DnsMessage) -> String {
+    let header = &message.header;
+    let mut string = format!(
+        ";; ->>HEADER<<- opcode: {}, status: {}, id: {}\n",
+        print_dig_opcode(header.opcode),
+        print_dig_rcode(message.rcode()),
+        header.id
+    );
+
+    let flags = [
+        (header.qr, "qr"),
+        (header.aa, "aa"),
+        (header.tc, "tc"),
+        (header.rd, "rd"),
+        (header.ra, "ra"),
+        (header.ad, "ad"),
+        (header.cd, "cd"),
+    ];
+    string.push_str(";; flags:");
+    for (_, flag) in flags.iter().filter(|(set, _)| *set) {
+        string.push_str(&format!(" {}", flag));
+    }
+    if header.z {
+        string.push_str("; MBZ: 0x0040");
+    }
+    string.push_str(&format!(
+        "; QUERY: {}, ANSWER: {}, AUTHORITY: {}, ADDITIONAL: {}\n",
+        header.qdcount, header.ancount, header.nscount, header.arcount
+    ));
+
+    if let Some(edns) = &message.edns {
+        string.push_str("\n;; OPT PSEUDOSECTION:\n");
+        string.push_str(&print_dig_edns(edns));
+    }
+
+    if !message.questions.is_empty() {
+        string.push_str("\n;; QUESTION SECTION:\n");
+        for question in &message.questions {
+            string.push_str(&print_columns(
+                &[
+                    (&format!(";{}", question.q_name), 32),
+                    (&question.q_class.to_string(), 40),
+                ],
+                &question.q_type.to_string(),
+            ));
+            string.push('\n');
+        }
+    }
+
+    let sections = [
+        ("ANSWER", &message.answers),
+        ("AUTHORITY", &message.authorities),
+        ("ADDITIONAL", &message.additionals),
+    ];
+    for (section, records) in sections {
+        if records.is_empty() {
+            continue;
+        }
+        string.push_str(&format!("\n;; {} SECTION:\n", section));
+        for record in records {
+            string.push_str(&print_dig_record(record));
+            string.push('\n');
+        }
+    }
+
+    return string;
+}
+#[cfg(test)]
+mod print_dig_message {
+    use std::net::Ipv4Addr;
+
+    use crate::dns::{
+        builder::QueryBuilder,
+        model::{DnsClass, DnsHeaderRcode, DnsResourceRecord, DnsType, Edns, EdnsOption, RData},
+        presentation::print_dig_message,
+    };
+
+    #[test]
+    fn case1() {
+        let mut message = QueryBuilder::new(4660)
+            .question(&"example.com.".parse().unwrap(), DnsType::A, DnsClass::IN)
+            .rd(true)
+            .edns(Edns {
+                udp_payload_size: 1232,
+                extended_rcode: 0,
+                version: 0,
+                dnssec_ok: true,
+                options: vec![EdnsOption {
+                    code: 10,
+                    data: vec![0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef],
+                }],
+            })
+            .build();
+        message.header.qr = true;
+        message.header.ra = true;
+        message.header.ancount = 1;
+        message.answers.push(DnsResourceRecord {
+            name: "example.com.".parse().unwrap(),
+            rr_type: DnsType::A,
+            rr_class: DnsClass::IN,
+            ttl: 86400,
+            rdata: RData::A(Ipv4Addr::new(192, 0, 2, 1)),
+        });
+
+        assert_eq!(
+            print_dig_message(&message),
+            ";; ->>HEADER<<- opcode: QUERY, status: NOERROR, id: 4660
+;; flags: qr rd ra; QUERY: 1, ANSWER: 1, AUTHORITY: 0, ADDITIONAL: 1
+
+;; OPT PSEUDOSECTION:
+; EDNS: version: 0, flags: do; udp: 1232
+; COOKIE: 0123456789abcdef
+
+;; QUESTION SECTION:
+;example.com.\t\t\tIN\tA
+
+;; ANSWER SECTION:
+example.com.\t\t86400\tIN\tA\t192.0.2.1
+"
+        );
+    }
+
+    #[test]
+    fn case2() {
+        // the extended rcode is combined into the status
+        let mut message = QueryBuilder::new(1)
+            .edns(Edns {
+                udp_payload_size: 512,
+                extended_rcode: 1,
+                version: 0,
+                dnssec_ok: false,
+                options: Vec::new(),
+            })
+            .build();
+        message.header.rcode = DnsHeaderRcode::NoError;
+        message.header.z = true;
+
+        assert_eq!(
+            print_dig_message(&message),
+            ";; ->>HEADER<<- opcode: QUERY, status: BADVERS, id: 1
+;; flags:; MBZ: 0x0040; QUERY: 0, ANSWER: 0, AUTHORITY: 0, ADDITIONAL: 1
+
+;; OPT PSEUDOSECTION:
+; EDNS: version: 0, flags:; udp: 512
+"
+        );
+    }
+}
+
+/// One record per line with absolute owner names, so that it parses back with any origin.
+/// see: <https://www.rfc-editor.org/rfc/rfc1035#section-5.1>
+pub fn print_zone(records: &[DnsResourceRecord]) -> String {
+    return records
+        .iter()
+        .map(|record| {
+            format!(
+                "{}\t{}\t{}\t{}\t{}\n",
+                record.name, record.ttl, record.rr_class, record.rr_type, record.rdata
+            )
+        })
+        .collect();
+}
+#[cfg(test)]
+mod print_zone {
+    use crate::dns::{name::DomainName, presentation::print_zone, zone::parse_zone};
+
+    #[test]
+    fn case1() {
+        let text = "$ORIGIN example.
+$TTL 3600
+@ SOA ns hostmaster 1 7200 3600 1209600 300
+  NS ns
+ns A 192.0.2.1
+txt 60 TXT \"a \\\"quoted\\\" string\" \"\\255\"
+caa CAA 0 issue \"ca.example.net\"
+srv SRV 0 5 5060 sip
+key DNSKEY 257 3 15 l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4=
+ds DS 60485 5 1 2BB183AF5F22588179A53B0A98631FAD1A292118
+nsec NSEC host A MX RRSIG NSEC TYPE1234
+n3 NSEC3 1 1 12 AABBCCDD 2T7B4G4VSA5SMI47K61MV5BV1A22BOJR A RRSIG
+other CLASS32 TYPE731 \\# 3 abcdef
+";
+        let records = parse_zone(text, "-", &DomainName::root()).unwrap();
+        let printed = print_zone(&records);
+
+        assert!(printed.starts_with(
+            "example.\t3600\tIN\tSOA\tns.example. hostmaster.example. 1 7200 3600 1209600 300\n"
+        ));
+        assert!(printed.ends_with("other.example.\t3600\tCLASS32\tTYPE731\t\\# 3 ABCDEF\n"));
+        assert_eq!(
+            parse_zone(&printed, "-", &"elsewhere.".parse().unwrap()).unwrap(),
+            records
+        );
+    }
+}
@@ -0,0 +1,752 @@
+#![allow(clippy::needless_return)]
+
+use std::collections::HashMap;
+
+use super::{
+    error::DnsSerializeError,
+    model::{
+        DnsClass, DnsHeaderSection, DnsMessage, DnsQuestionSectionEntry, DnsResourceRecord,
+        DnsType, Edns, RData,
+    },
+    name::DomainName,
+};
+
+// pointers have 14 bits, so only names in the first 16KiB can be referred to
+const MAX_POINTER_OFFSET: usize = 0b00111111_11111111;
+
+struct DnsMessageWriter {
+    buffer: Vec<u8>,
+    // name suffix -> offset of its first occurrence in the buffer.
+    // suffixes are matched exactly so that the case of names survives a round trip
+    name_offsets: HashMap<Vec<Vec<u8>>, usize>,
+    // names are never compressed when false
+    compress: bool,
+}
+impl DnsMessageWriter {
+    fn new() -> Self {
+        return DnsMessageWriter {
+            buffer: Vec::new(),
+            name_offsets: HashMap::new(),
+            compress: true,
+        };
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.buffer.push(value);
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.buffer.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.buffer.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    // bytes after a single length byte, as a character string.
+    // see: https://www.rfc-editor.org/rfc/rfc1035#section-3.3
+    fn write_character_string(
+        &mut self,
+        field: &'static str,
+        bytes: &[u8],
+    ) -> Result<(), DnsSerializeError> {
+        let length = u8::try_from(bytes.len())
+            .map_err(|_| DnsSerializeError::TooLong(field, bytes.len()))?;
+        self.write_u8(length);
+        self.write_bytes(bytes);
+        return Ok(());
+    }
+
+    // see: https://www.rfc-editor.org/rfc/rfc1035#section-4.1.4
+    fn write_name(&mut self, name: &DomainName, compress: bool) {
+        let labels: Vec<&[u8]> = name.labels().collect();
+
+        for index in 0..labels.len() {
+            let suffix: Vec<Vec<u8>> = labels[index..].iter().map(|label| label.to_vec()).collect();
+            if compress
+                && self.compress
+                && let Some(&offset) = self.name_offsets.get(&suffix)
+            {
+                self.write_u16(0b11000000_00000000 | offset as u16);
+                return;
+            }
+
+            if self.buffer.len() <= MAX_POINTER_OFFSET {
+                self.name_offsets.insert(suffix, self.buffer.len());
+            }
+
+            self.write_u8(labels[index].len() as u8);
+            self.write_bytes(labels[index]);
+        }
+
+        self.write_u8(0);
+    }
+
+    fn write_header(&mut self, header: &DnsHeaderSection, counts: [u16; 4]) {
+        self.write_u16(header.id);
+        self.write_u8(
+            (header.qr as u8) << 7
+                | (u8::from(header.opcode) & 0b00001111) << 3
+                | (header.aa as u8) << 2
+                | (header.tc as u8) << 1
+                | header.rd as u8,
+        );
+        self.write_u8(
+            (header.ra as u8) << 7
+                | (header.z as u8) << 6
+                | (header.ad as u8) << 5
+                | (header.cd as u8) << 4
+                | (u16::from(header.rcode) & 0b00001111) as u8,
+        );
+        for count in counts {
+            self.write_u16(count);
+        }
+    }
+
+    fn write_question(&mut self, question: &DnsQuestionSectionEntry) {
+        self.write_name(&question.q_name, true);
+        self.write_u16(question.q_type.into());
+        self.write_u16(question.q_class.into());
+    }
+
+    // only the types defined in rfc1035 may be compressed inside rdata.
+    // see: https://www.rfc-editor.org/rfc/rfc3597#section-4
+    fn write_rdata(&mut self, rdata: &RData) -> Result<(), DnsSerializeError> {
+        match rdata {
+            RData::A(address) => self.write_bytes(&address.octets()),
+            RData::NS(name) => self.write_name(name, true),
+            RData::CNAME(name) => self.write_name(name, true),
+            RData::SOA {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            } => {
+                self.write_name(mname, true);
+                self.write_name(rname, true);
+                self.write_u32(*serial);
+                self.write_u32(*refresh);
+                self.write_u32(*retry);
+                self.write_u32(*expire);
+                self.write_u32(*minimum);
+            }
+            RData::PTR(name) => self.write_name(name, true),
+            RData::MX {
+                preference,
+                exchange,
+            } => {
+                self.write_u16(*preference);
+                self.write_name(exchange, true);
+            }
+            RData::TXT(strings) => {
+                for string in strings {
+                    self.write_character_string("character string", string)?;
+                }
+            }
+            RData::AAAA(address) => self.write_bytes(&address.octets()),
+            RData::SRV {
+                priority,
+                weight,
+                port,
+                target,
+            } => {
+                self.write_u16(*priority);
+                self.write_u16(*weight);
+                self.write_u16(*port);
+                self.write_name(target, false);
+            }
+            RData::CAA { flags, tag, value } => {
+                self.write_u8(*flags);
+                self.write_character_string("caa tag", tag.as_bytes())?;
+                self.write_bytes(value);
+            }
+            RData::OPT(options) => {
+                for option in options {
+                    self.write_u16(option.code);
+                    let length = u16::try_from(option.data.len()).map_err(|_| {
+                        DnsSerializeError::TooLong("option data", option.data.len())
+                    })?;
+                    self.write_u16(length);
+                    self.write_bytes(&option.data);
+                }
+            }
+            RData::DS {
+                key_tag,
+                algorithm,
+                digest_type,
+                digest,
+            } => {
+                self.write_u16(*key_tag);
+                self.write_u8(*algorithm);
+                self.write_u8(*digest_type);
+                self.write_bytes(digest);
+            }
+            RData::RRSIG {
+                type_covered,
+                algorithm,
+                labels,
+                original_ttl,
+                expiration,
+                inception,
+                key_tag,
+                signer_name,
+                signature,
+            } => {
+                self.write_u16((*type_covered).into());
+                self.write_u8(*algorithm);
+                self.write_u8(*labels);
+                self.write_u32(*original_ttl);
+                self.write_u32(*expiration);
+                self.write_u32(*inception);
+                self.write_u16(*key_tag);
+                self.write_name(signer_name, false);
+                self.write_bytes(signature);
+            }
+            RData::NSEC {
+                next_domain_name,
+                types,
+            } => {
+                self.write_name(next_domain_name, false);
+                self.write_type_bitmap(types);
+            }
+            RData::DNSKEY {
+                flags,
+                protocol,
+                algorithm,
+                public_key,
+            } => {
+                self.write_u16(*flags);
+                self.write_u8(*protocol);
+                self.write_u8(*algorithm);
+                self.write_bytes(public_key);
+            }
+            RData::NSEC3 {
+                hash_algorithm,
+                flags,
+                iterations,
+                salt,
+                next_hashed_owner_name,
+                types,
+            } => {
+                self.write_u8(*hash_algorithm);
+                self.write_u8(*flags);
+                self.write_u16(*iterations);
+                self.write_character_string("salt", salt)?;
+                self.write_character_string("hash", next_hashed_owner_name)?;
+                self.write_type_bitmap(types);
+            }
+            RData::NSEC3PARAM {
+                hash_algorithm,
+                flags,
+                iterations,
+                salt,
+            } => {
+                self.write_u8(*hash_algorithm);
+                self.write_u8(*flags);
+                self.write_u16(*iterations);
+                self.write_character_string("salt", salt)?;
+            }
+            RData::Unknown(data) => self.write_bytes(data),
+        }
+
+        return Ok(());
+    }
+
+    // types are grouped into windows of 256, empty windows are omitted.
+    // see: https://www.rfc-editor.org/rfc/rfc4034#section-4.1.2
+    fn write_type_bitmap(&mut self, types: &[DnsType]) {
+        let mut values: Vec<u16> = types.iter().map(|&t| t.into()).collect();
+        values.sort();
+        values.dedup();
+
+        for window in 0..=255_u8 {
+            let bits: Vec<u8> = values
+                .iter()
+                .filter(|&&value| (value >> 8) as u8 == window)
+                .map(|&value| value as u8)
+                .collect();
+            let Some(&last) = bits.last() else {
+                continue;
+            };
+
+            let mut bitmap = vec![0_u8; last as usize / 8 + 1];
+            for bit in bits {
+                bitmap[bit as usize / 8] |= 0b10000000 >> (bit % 8);
+            }
+            self.write_u8(window);
+            self.write_u8(bitmap.len() as u8);
+            self.write_bytes(&bitmap);
+        }
+    }
+
+    fn write_resource_record(&mut self, rr: &DnsResourceRecord) -> Result<(), DnsSerializeError> {
+        self.write_name(&rr.name, true);
+        self.write_u16(rr.rr_type.into());
+        self.write_u16(rr.rr_class.into());
+        self.write_u32(rr.ttl);
+
+        // rd_length is known only after writing rdata
+        let rd_length_offset = self.buffer.len();
+        self.write_u16(0);
+        self.write_rdata(&rr.rdata)?;
+        let rd_length = self.buffer.len() - rd_length_offset - 2;
+        let rd_length =
+            u16::try_from(rd_length).map_err(|_| DnsSerializeError::TooLong("rdata", rd_length))?;
+        self.buffer[rd_length_offset..rd_length_offset + 2]
+            .copy_from_slice(&rd_length.to_be_bytes());
+        return Ok(());
+    }
+}
+
+// see: https://www.rfc-editor.org/rfc/rfc6891#section-6.1.3
+pub(super) fn create_dns_opt_resource_record(edns: &Edns) -> DnsResourceRecord {
+    return DnsResourceRecord {
+        name: DomainName::root(),
+        rr_type: DnsType::OPT,
+        rr_class: DnsClass::from(edns.udp_payload_size),
+        ttl: (edns.extended_rcode as u32) << 24
+            | (edns.version as u32) << 16
+            | (edns.dnssec_ok as u32) << 15,
+        rdata: RData::OPT(edns.options.clone()),
+    };
+}
+
+impl DnsMessage {
+    // section counts are taken from the sections, not from the header. Fails when a field is too
+    // long for its length on the wire.
+    pub fn to_wire(&self) -> Result<Vec<u8>, DnsSerializeError> {
+        let mut writer = DnsMessageWriter::new();
+
+        writer.write_header(
+            &self.header,
+            [
+                self.questions.len() as u16,
+                self.answers.len() as u16,
+                self.authorities.len() as u16,
+                (self.additionals.len() + self.edns.is_some() as usize) as u16,
+            ],
+        );
+        for question in &self.questions {
+            writer.write_question(question);
+        }
+        for rr in &self.answers {
+            writer.write_resource_record(rr)?;
+        }
+        for rr in &self.authorities {
+            writer.write_resource_record(rr)?;
+        }
+        for rr in &self.additionals {
+            writer.write_resource_record(rr)?;
+        }
+        if let Some(edns) = &self.edns {
+            writer.write_resource_record(&create_dns_opt_resource_record(edns))?;
+        }
+
+        return Ok(writer.buffer);
+    }
+}
+#[cfg(test)]
+mod to_wire {
+    use crate::dns::{
+        error::DnsSerializeError,
+        fixtures::{create_message, create_rr},
+        model::{DnsHeaderRcode, DnsType, Edns, EdnsOption, RData},
+        parser::parse_dns_message,
+    };
+
+    #[test]
+    fn case1() {
+        let message = create_message(
+            DnsHeaderRcode::NoError,
+            vec![create_rr(
+                "example.com.",
+                DnsType::A,
+                RData::A("192.0.2.1".parse().unwrap()),
+            )],
+            vec![],
+            vec![],
+        );
+
+        assert_eq!(
+            message.to_wire().unwrap(),
+            vec![
+                0x12, 0x34,       // id
+                0b10000101, // qr opecode=Query aa !tc rd
+                0b00100000, // !ra Z ad !cd rcode=NoError
+                0x00, 0x01, // qdcount
+                0x00, 0x01, // ancount
+                0x00, 0x00, // nscount
+                0x00, 0x00, // arcount
+                // questions
+                7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0, // qname
+                0x00, 0x01, // qtype
+                0x00, 0x01, // qclass
+                // answers
+                0b11000000, 12, // name: pointer to qname
+                0x00, 0x01, // rr type
+                0x00, 0x01, // rr class
+                0x00, 0x00, 0x0e, 0x10, // ttl
+                0x00, 0x04, // rd_length
+                192, 0, 2, 1, // rdata
+            ]
+        );
+    }
+
+    #[test]
+    fn case2() {
+        let message = create_message(
+            DnsHeaderRcode::NoError,
+            vec![
+                create_rr(
+                    "www.example.com.",
+                    DnsType::CNAME,
+                    RData::CNAME("example.com.".parse().unwrap()),
+                ),
+                create_rr(
+                    "example.com.",
+                    DnsType::MX,
+                    RData::MX {
+                        preference: 10,
+                        exchange: "mail.example.com.".parse().unwrap(),
+                    },
+                ),
+                create_rr(
+                    "example.com.",
+                    DnsType::TXT,
+                    RData::TXT(vec![b"v=spf1 -all".to_vec(), vec![]]),
+                ),
+                create_rr(
+                    "_sip._tcp.example.com.",
+                    DnsType::SRV,
+                    RData::SRV {
+                        priority: 1,
+                        weight: 5,
+                        port: 5060,
+                        target: "sip.example.com.".parse().unwrap(),
+                    },
+                ),
+                create_rr(
+                    "example.com.",
+                    DnsType::CAA,
+                    RData::CAA {
+                        flags: 0,
+                        tag: "issue".to_string(),
+                        value: b"letsencrypt.org".to_vec(),
+                    },
+                ),
+                create_rr(
+                    "1.2.0.192.in-addr.arpa.",
+                    DnsType::PTR,
+                    RData::PTR("example.com.".parse().unwrap()),
+                ),
+                create_rr(
+                    "example.com.",
+                    DnsType::Unknown(12345),
+                    RData::Unknown(vec![0x0a, 0x00, 0x00, 0x01]),
+                ),
+            ],
+            vec![
+                create_rr(
+                    "example.com.",
+                    DnsType::NS,
+                    RData::NS("ns.example.com.".parse().unwrap()),
+                ),
+                create_rr(
+                    "example.com.",
+                    DnsType::SOA,
+                    RData::SOA {
+                        mname: "ns.example.com.".parse().unwrap(),
+                        rname: "hostmaster.example.com.".parse().unwrap(),
+                        serial: 2024010101,
+                        refresh: 7200,
+                        retry: 3600,
+                        expire: 1209600,
+                        minimum: 300,
+                    },
+                ),
+            ],
+            vec![create_rr(
+                "ns.example.com.",
+                DnsType::AAAA,
+                RData::AAAA("2001:db8::53".parse().unwrap()),
+            )],
+        );
+
+        assert_eq!(
+            parse_dns_message(&message.to_wire().unwrap()).unwrap(),
+            message
+        );
+    }
+
+    #[test]
+    fn case3() {
+        let bytes = vec![
+            0x00, 0x01, // id
+            0x80, // qr opecode=Query !aa !tc !rd
+            0x00, // !ra ZZZ rcode=NoError
+            0x00, 0x01, // qdcount=1
+            0x00, 0x01, // ancount=1
+            0x00, 0x00, // nscount=0
+            0x00, 0x00, // arcount=0
+            // questions
+            0x03, b'c', b'o', b'm', 0x00, 0x00, 0x02, 0x00, 0x01, // com. IN NS
+            // answers
+            0xc0, 0x0c, 0x00, 0x02, 0x00, 0x01, 0x00, 0x02, 0xa3, 0x00, 0x00, 0x04, 0x01, b'a',
+            0xc0, 0x0c, // com. 172800 IN NS a.com.
+        ];
+        let message = parse_dns_message(&bytes).unwrap();
+
+        assert_eq!(message.to_wire().unwrap(), bytes);
+    }
+
+    #[test]
+    fn case4() {
+        let mut message = create_message(DnsHeaderRcode::NoError, vec![], vec![], vec![]);
+        message.header.arcount = 1;
+        message.edns = Some(Edns {
+            udp_payload_size: 1232,
+            extended_rcode: 1,
+            version: 0,
+            dnssec_ok: true,
+            options: vec![EdnsOption {
+                code: 10,
+                data: vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08],
+            }],
+        });
+
+        let bytes = message.to_wire().unwrap();
+        assert_eq!(
+            bytes[bytes.len() - 23..],
+            [
+                0x00, // root
+                0x00, 0x29, // type=OPT
+                0x04, 0xd0, // udp payload size=1232
+                0x01, 0x00, 0x80, 0x00, // extended rcode=1 version=0 do
+                0x00, 0x0c, // rd_length
+                0x00, 0x0a, 0x00, 0x08, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+                0x08, // COOKIE
+            ]
+        );
+        assert_eq!(parse_dns_message(&bytes).unwrap(), message);
+    }
+    #[test]
+    fn case5() {
+        let message = create_message(
+            DnsHeaderRcode::NoError,
+            vec![
+                create_rr(
+                    "example.com.",
+                    DnsType::DNSKEY,
+                    RData::DNSKEY {
+                        flags: 257,
+                        protocol: 3,
+                        algorithm: 13,
+                        public_key: vec![0x01, 0x02, 0x03, 0x04],
+                    },
+                ),
+                create_rr(
+                    "example.com.",
+                    DnsType::RRSIG,
+                    RData::RRSIG {
+                        type_covered: DnsType::DNSKEY,
+                        algorithm: 13,
+                        labels: 2,
+                        original_ttl: 3600,
+                        expiration: 1735689599,
+                        inception: 1733097599,
+                        key_tag: 12345,
+                        signer_name: "example.com.".parse().unwrap(),
+                        signature: vec![0x05, 0x06, 0x07, 0x08],
+                    },
+                ),
+            ],
+            vec![
+                create_rr(
+                    "example.com.",
+                    DnsType::NSEC,
+                    RData::NSEC {
+                        next_domain_name: "www.example.com.".parse().unwrap(),
+                        types: vec![
+                            DnsType::NS,
+                            DnsType::SOA,
+                            DnsType::RRSIG,
+                            DnsType::NSEC,
+                            DnsType::DNSKEY,
+                            DnsType::CAA,
+                        ],
+                    },
+                ),
+                create_rr(
+                    "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example.com.",
+                    DnsType::NSEC3,
+                    RData::NSEC3 {
+                        hash_algorithm: 1,
+                        flags: 1,
+                        iterations: 0,
+                        salt: vec![],
+                        next_hashed_owner_name: vec![0xff; 20],
+                        types: vec![DnsType::A, DnsType::RRSIG],
+                    },
+                ),
+                create_rr(
+                    "example.com.",
+                    DnsType::NSEC3PARAM,
+                    RData::NSEC3PARAM {
+                        hash_algorithm: 1,
+                        flags: 0,
+                        iterations: 0,
+                        salt: vec![0xab],
+                    },
+                ),
+            ],
+            vec![create_rr(
+                "example.com.",
+                DnsType::DS,
+                RData::DS {
+                    key_tag: 12345,
+                    algorithm: 13,
+                    digest_type: 2,
+                    digest: vec![0x0a; 32],
+                },
+            )],
+        );
+
+        assert_eq!(
+            parse_dns_message(&message.to_wire().unwrap()).unwrap(),
+            message
+        );
+    }
+
+    #[test]
+    fn case6() {
+        let message = create_message(
+            DnsHeaderRcode::NoError,
+            vec![create_rr(
+                "example.com.",
+                DnsType::NSEC,
+                RData::NSEC {
+                    next_domain_name: "example.com.".parse().unwrap(),
+                    types: vec![DnsType::CAA, DnsType::A, DnsType::A],
+                },
+            )],
+            vec![],
+            vec![],
+        );
+
+        let bytes = message.to_wire().unwrap();
+        assert_eq!(
+            bytes[bytes.len() - 19..],
+            [
+                7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm',
+                0, // next domain name is never compressed
+                0x00, 0x01, 0x40, // window 0: A
+                0x01, 0x01, 0x40, // window 1: CAA
+            ]
+        );
+    }
+
+    #[test]
+    fn case7() {
+        // lengths written in a single byte
+        let too_long = |rr_type: DnsType, rdata: RData| {
+            let message = create_message(
+                DnsHeaderRcode::NoError,
+                vec![create_rr("example.com.", rr_type, rdata)],
+                vec![],
+                vec![],
+            );
+            return message.to_wire();
+        };
+
+        assert_eq!(
+            too_long(
+                DnsType::TXT,
+                RData::TXT(vec![vec![b'x'; 255], vec![b'x'; 256]])
+            ),
+            Err(DnsSerializeError::TooLong("character string", 256))
+        );
+        assert_eq!(
+            too_long(
+                DnsType::CAA,
+                RData::CAA {
+                    flags: 0,
+                    tag: "x".repeat(256),
+                    value: vec![],
+                }
+            ),
+            Err(DnsSerializeError::TooLong("caa tag", 256))
+        );
+        assert_eq!(
+            too_long(
+                DnsType::NSEC3,
+                RData::NSEC3 {
+                    hash_algorithm: 1,
+                    flags: 0,
+                    iterations: 0,
+                    salt: vec![0xab],
+                    next_hashed_owner_name: vec![0xcd; 256],
+                    types: vec![],
+                }
+            ),
+            Err(DnsSerializeError::TooLong("hash", 256))
+        );
+        assert_eq!(
+            too_long(
+                DnsType::NSEC3PARAM,
+                RData::NSEC3PARAM {
+                    hash_algorithm: 1,
+                    flags: 0,
+                    iterations: 0,
+                    salt: vec![0xab; 300],
+                }
+            ),
+            Err(DnsSerializeError::TooLong("salt", 300))
+        );
+        assert!(too_long(DnsType::TXT, RData::TXT(vec![vec![b'x'; 255]])).is_ok());
+    }
+}
+
+impl RData {
+    // uncompressed, as rdata is digested and signed in DNSSEC
+    pub fn to_wire(&self) -> Result<Vec<u8>, DnsSerializeError> {
+        let mut writer = DnsMessageWriter::new();
+        writer.compress = false;
+        writer.write_rdata(self)?;
+
+        return Ok(writer.buffer);
+    }
+}
+#[cfg(test)]
+mod rdata_to_wire {
+    use crate::dns::{model::RData, name::DomainName};
+
+    #[test]
+    fn case1() {
+        let rdata = RData::SOA {
+            mname: "ns.example.com.".parse().unwrap(),
+            rname: "admin.example.com.".parse().unwrap(),
+            serial: 1,
+            refresh: 2,
+            retry: 3,
+            expire: 4,
+            minimum: 5,
+        };
+
+        let mut expected = "ns.example.com.".parse::<DomainName>().unwrap().to_wire();
+        expected.extend_from_slice(
+            &"admin.example.com."
+                .parse::<DomainName>()
+                .unwrap()
+                .to_wire(),
+        );
+        expected.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0, 5]);
+        assert_eq!(rdata.to_wire().unwrap(), expected);
+    }
+}
@@ -1,3 +1,7 @@
+#![allow(clippy::needless_return)]
+
+use super::{encoding::parse_hex, model::RData, name::DomainName};
+
 pub const ROOT_IP_ADDRS: &[&str] = &[
     "198.41.0.4",     // A
     "170.247.170.2",  // B
@@ -14,22 +18,36 @@ pub const ROOT_IP_ADDRS: &[&str] = &[
     "202.12.27.33",   // M
 ];
 
-fn print_dns_name(name_str: &str) -> Vec<u8> {
-    if name_str.len() > 255 {
-        panic!("too long domain name {}", name_str);
-    }
+// DS records of the root zone KSKs. see: https://data.iana.org/root-anchors/root-anchors.xml
+pub const ROOT_TRUST_ANCHORS: &[(u16, u8, u8, &str)] = &[
+    (
+        20326,
+        8,
+        2,
+        "E06D44B80B8F1D39A95C0B0D7C65D08458E880409BBC683457104237C7F8EC8D",
+    ), // KSK-2017
+    (
+        38696,
+        8,
+        2,
+        "683D2D0ACB8C9B712A1948B27F741219298D0A450D612C483AF444A4C0FB2B16",
+    ), // KSK-2024
+];
 
-    let long_label = name_str.split('.').find(|label_str| label_str.len() > 63);
-    if long_label.is_some() {
-        panic!("too long label {}", name_str);
-    }
+pub fn create_root_trust_anchors() -> Vec<RData> {
+    return ROOT_TRUST_ANCHORS
+        .iter()
+        .map(|&(key_tag, algorithm, digest_type, digest)| RData::DS {
+            key_tag,
+            algorithm,
+            digest_type,
+            digest: parse_hex(digest).unwrap(),
+        })
+        .collect();
+}
 
-    return name_str
-        .split('.')
-        .map(|str| str.to_string().into_bytes())
-        .map(|label_bytes| [vec![label_bytes.len() as u8], label_bytes].concat())
-        .collect::<Vec<Vec<u8>>>()
-        .concat();
+pub fn print_dns_name(name_str: &str) -> Result<Vec<u8>, String> {
+    return Ok(name_str.parse::<DomainName>()?.to_wire());
 }
 #[cfg(test)]
 mod print_dns_name {
@@ -37,7 +55,7 @@ mod print_dns_name {
 
     #[test]
     fn case1() {
-        let bytes = print_dns_name("example.com.");
+        let bytes = print_dns_name("example.com.").unwrap();
 
         assert_eq!(
             bytes,
@@ -48,34 +66,32 @@ mod print_dns_name {
     }
 
     #[test]
-    #[should_panic]
     fn case2() {
-        print_dns_name(
+        let result = print_dns_name(
             "longnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongnamelongname",
         );
+
+        assert!(result.unwrap_err().starts_with("too long label: "));
     }
 
     #[test]
-    #[should_panic]
     fn case3() {
-        print_dns_name("longlabellonglabellonglabellonglabellonglabellonglabellonglabell.com");
-    }
-}
+        let result =
+            print_dns_name("longlabellonglabellonglabellonglabellonglabellonglabellonglabell.com");
 
-pub fn create_dns_a_question_message(id_num: u16, name_str: &str) -> Vec<u8> {
-    let id = id_num.to_be_bytes().to_vec();
-    let flags = 0_u16.to_be_bytes().to_vec(); // !qr opecode=Query !aa !tc !rd !ra ZZZ rcode=none
-    let qcount = 1_u16.to_be_bytes().to_vec();
-    let anount = 0_u16.to_be_bytes().to_vec();
-    let nsount = 0_u16.to_be_bytes().to_vec();
-    let arount = 0_u16.to_be_bytes().to_vec();
+        assert_eq!(
+            result,
+            Err(
+                "too long label: longlabellonglabellonglabellonglabellonglabellonglabellonglabell.com"
+                    .to_string()
+            )
+        );
+    }
 
-    let qname = print_dns_name(name_str);
-    let qtype = 1_u16.to_be_bytes().to_vec(); // A
-    let qclass = 1_u16.to_be_bytes().to_vec(); // IN
+    #[test]
+    fn case4() {
+        let bytes = print_dns_name("a\\.b.\\255.").unwrap();
 
-    return [
-        id, flags, qcount, anount, nsount, arount, qname, qtype, qclass,
-    ]
-    .concat();
+        assert_eq!(bytes, vec![3, b'a', b'.', b'b', 1, 0xff, 0]);
+    }
 }
@@ -0,0 +1,994 @@
+#![allow(clippy::needless_return)]
+
+use std::{fs, path::Path, str::FromStr};
+
+use super::{
+    encoding::{parse_base32hex, parse_base64, parse_dnssec_time, parse_hex},
+    error::ZoneParseError,
+    model::{DnsClass, DnsResourceRecord, DnsType, RData},
+    name::DomainName,
+    parser::parse_dns_rdata,
+};
+
+// $INCLUDE chains deeper than this are taken as a loop
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+#[derive(PartialEq, Debug, Clone)]
+struct Token {
+    // escapes are kept as written, quotes are removed
+    text: String,
+    quoted: bool,
+}
+
+// one record or directive, which may span lines inside parentheses
+#[derive(PartialEq, Debug, Clone)]
+struct Entry {
+    line: usize,
+    // a line starting with a blank reuses the previous owner
+    blank_owner: bool,
+    tokens: Vec<Token>,
+}
+
+// see: https://www.rfc-editor.org/rfc/rfc1035#section-5.1
+fn tokenize(text: &str) -> Result<Vec<Entry>, (usize, String)> {
+    let mut entries = Vec::new();
+    let mut entry = Entry {
+        line: 1,
+        blank_owner: false,
+        tokens: Vec::new(),
+    };
+    let mut token: Option<Token> = None;
+    let mut line = 1;
+    let mut parentheses = 0;
+    let mut in_quotes = false;
+    let mut at_line_start = true;
+
+    let mut chars = text.chars().peekable();
+    while let Some(x) = chars.next() {
+        if at_line_start && parentheses == 0 {
+            entry.line = line;
+            entry.blank_owner = x == ' ' || x == '\t';
+        }
+        at_line_start = false;
+
+        if in_quotes {
+            let current = token.as_mut().unwrap();
+            match x {
+                '"' => in_quotes = false,
+                '\\' => {
+                    current.text.push(x);
+                    if let Some(escaped) = chars.next() {
+                        if escaped == '\n' {
+                            line += 1;
+                        }
+                        current.text.push(escaped);
+                    }
+                }
+                _ => {
+                    if x == '\n' {
+                        line += 1;
+                    }
+                    current.text.push(x);
+                }
+            }
+            continue;
+        }
+
+        match x {
+            ' ' | '\t' | '\r' => entry.tokens.extend(token.take()),
+            ';' => {
+                entry.tokens.extend(token.take());
+                while chars.next_if(|&y| y != '\n').is_some() {}
+            }
+            '(' => {
+                entry.tokens.extend(token.take());
+                parentheses += 1;
+            }
+            ')' => {
+                entry.tokens.extend(token.take());
+                if parentheses == 0 {
+                    return Err((line, "unbalanced parentheses".to_string()));
+                }
+                parentheses -= 1;
+            }
+            '"' => {
+                entry.tokens.extend(token.take());
+                token = Some(Token {
+                    text: String::new(),
+                    quoted: true,
+                });
+                in_quotes = true;
+            }
+            '\n' => {
+                entry.tokens.extend(token.take());
+                line += 1;
+                at_line_start = true;
+                if parentheses == 0 && !entry.tokens.is_empty() {
+                    entries.push(entry.clone());
+                    entry.tokens.clear();
+                }
+            }
+            _ => {
+                let current = token.get_or_insert(Token {
+                    text: String::new(),
+                    quoted: false,
+                });
+                current.text.push(x);
+                if x == '\\'
+                    && let Some(escaped) = chars.next()
+                {
+                    current.text.push(escaped);
+                }
+            }
+        }
+    }
+
+    if in_quotes {
+        return Err((line, "unterminated quoted string".to_string()));
+    }
+    if parentheses != 0 {
+        return Err((line, "unbalanced parentheses".to_string()));
+    }
+    entry.tokens.extend(token.take());
+    if !entry.tokens.is_empty() {
+        entries.push(entry);
+    }
+
+    return Ok(entries);
+}
+#[cfg(test)]
+mod tokenize {
+    use crate::dns::zone::tokenize;
+
+    fn texts(text: &str) -> Vec<(usize, bool, Vec<String>)> {
+        return tokenize(text)
+            .unwrap()
+            .into_iter()
+            .map(|entry| {
+                (
+                    entry.line,
+                    entry.blank_owner,
+                    entry.tokens.into_iter().map(|token| token.text).collect(),
+                )
+            })
+            .collect();
+    }
+
+    #[test]
+    fn case1() {
+        assert_eq!(
+            texts("a 1 IN A 192.0.2.1 ; comment\n\n  IN TXT \"x ; y\" z\\ w\n"),
+            vec![
+                (
+                    1,
+                    false,
+                    vec!["a", "1", "IN", "A", "192.0.2.1"]
+                        .into_iter()
+                        .map(String::from)
+                        .collect()
+                ),
+                (
+                    3,
+                    true,
+                    vec!["IN", "TXT", "x ; y", "z\\ w"]
+                        .into_iter()
+                        .map(String::from)
+                        .collect()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn case2() {
+        assert_eq!(
+            texts("@ SOA ns admin (\n  1 ; serial\n  2 3 4 5 )\nb A 192.0.2.2"),
+            vec![
+                (
+                    1,
+                    false,
+                    vec!["@", "SOA", "ns", "admin", "1", "2", "3", "4", "5"]
+                        .into_iter()
+                        .map(String::from)
+                        .collect()
+                ),
+                (
+                    4,
+                    false,
+                    vec!["b", "A", "192.0.2.2"]
+                        .into_iter()
+                        .map(String::from)
+                        .collect()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn case3() {
+        assert_eq!(
+            tokenize("a A (\n1.2.3.4").unwrap_err(),
+            (2, "unbalanced parentheses".to_string())
+        );
+        assert_eq!(
+            tokenize("a A )").unwrap_err(),
+            (1, "unbalanced parentheses".to_string())
+        );
+        assert_eq!(
+            tokenize("a TXT \"x\n").unwrap_err(),
+            (2, "unterminated quoted string".to_string())
+        );
+    }
+}
+
+// names without a trailing dot are relative to the origin, "@" is the origin itself
+fn parse_name(name_str: &str, origin: &DomainName) -> Result<DomainName, String> {
+    if name_str == "@" {
+        return Ok(origin.clone());
+    }
+
+    let name: DomainName = name_str.parse()?;
+    // a trailing dot counts only when it is not escaped
+    let trailing_backslashes = name_str
+        .trim_end_matches('.')
+        .chars()
+        .rev()
+        .take_while(|&x| x == '\\')
+        .count();
+    if name_str.ends_with('.') && trailing_backslashes % 2 == 0 {
+        return Ok(name);
+    }
+
+    let mut labels: Vec<Vec<u8>> = name.labels().map(|label| label.to_vec()).collect();
+    labels.extend(origin.labels().map(|label| label.to_vec()));
+
+    return DomainName::from_labels(labels).map_err(|message| format!("{}: {}", message, name_str));
+}
+#[cfg(test)]
+mod parse_name {
+    use crate::dns::{name::DomainName, zone::parse_name};
+
+    #[test]
+    fn case1() {
+        let origin: DomainName = "example.com.".parse().unwrap();
+
+        assert_eq!(parse_name("@", &origin).unwrap(), origin);
+        assert_eq!(
+            parse_name("www", &origin).unwrap().to_string(),
+            "www.example.com."
+        );
+        assert_eq!(
+            parse_name("www.example.net.", &origin).unwrap().to_string(),
+            "www.example.net."
+        );
+        assert_eq!(
+            parse_name("a\\.", &origin).unwrap().to_string(),
+            "a\\..example.com."
+        );
+    }
+}
+
+// plain seconds or BIND style units such as 1h30m
+fn parse_ttl(ttl_str: &str) -> Result<u32, String> {
+    if let Ok(ttl) = ttl_str.parse::<u32>() {
+        return Ok(ttl);
+    }
+
+    let mut ttl = 0_u64;
+    let mut value = None;
+    for x in ttl_str.chars() {
+        if let Some(digit) = x.to_digit(10) {
+            value = Some(value.unwrap_or(0_u64) * 10 + digit as u64);
+            if value > Some(u32::MAX as u64) {
+                return Err(format!("bad ttl: {}", ttl_str));
+            }
+            continue;
+        }
+
+        let unit = match x.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            'w' => 604800,
+            _ => return Err(format!("bad ttl: {}", ttl_str)),
+        };
+        ttl += value.take().ok_or(format!("bad ttl: {}", ttl_str))? * unit;
+    }
+    if value.is_some() || ttl > u32::MAX as u64 {
+        return Err(format!("bad ttl: {}", ttl_str));
+    }
+
+    return Ok(ttl as u32);
+}
+#[cfg(test)]
+mod parse_ttl {
+    use crate::dns::zone::parse_ttl;
+
+    #[test]
+    fn case1() {
+        assert_eq!(parse_ttl("3600").unwrap(), 3600);
+        assert_eq!(parse_ttl("1h30m").unwrap(), 5400);
+        assert_eq!(parse_ttl("1W2D").unwrap(), 777600);
+        assert!(parse_ttl("1h30").is_err());
+        assert!(parse_ttl("h").is_err());
+        assert!(parse_ttl("IN").is_err());
+    }
+}
+
+// \DDD and \X escapes are resolved. see: https://www.rfc-editor.org/rfc/rfc1035#section-5.1
+fn parse_character_string(token: &Token) -> Result<Vec<u8>, String> {
+    let bytes = token.text.as_bytes();
+    let mut string = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] != b'\\' {
+            string.push(bytes[index]);
+            index += 1;
+            continue;
+        }
+
+        let escaped = &bytes[index + 1..];
+        if escaped.len() >= 3 && escaped[..3].iter().all(|x| x.is_ascii_digit()) {
+            let value = escaped[..3]
+                .iter()
+                .fold(0_u16, |value, x| value * 10 + (x - b'0') as u16);
+            if value > 255 {
+                return Err(format!("bad escape: {}", token.text));
+            }
+            string.push(value as u8);
+            index += 4;
+        } else if let Some(&x) = escaped.first() {
+            string.push(x);
+            index += 2;
+        } else {
+            return Err(format!("bad escape: {}", token.text));
+        }
+    }
+    if string.len() > 255 {
+        return Err(format!("too long character string: {}", token.text));
+    }
+
+    return Ok(string);
+}
+
+// the rdata fields of an entry, consumed from the left
+struct Fields<'a> {
+    tokens: &'a [Token],
+    origin: &'a DomainName,
+}
+impl<'a> Fields<'a> {
+    fn next(&mut self) -> Result<&'a Token, String> {
+        let (first, rest) = self
+            .tokens
+            .split_first()
+            .ok_or("missing rdata field".to_string())?;
+        self.tokens = rest;
+
+        return Ok(first);
+    }
+
+    fn parse<T: FromStr>(&mut self) -> Result<T, String> {
+        let token = self.next()?;
+        return token
+            .text
+            .parse::<T>()
+            .map_err(|_| format!("bad rdata field: {}", token.text));
+    }
+
+    fn name(&mut self) -> Result<DomainName, String> {
+        let token = self.next()?;
+        return parse_name(&token.text, self.origin);
+    }
+
+    // a CAA tag is one or more letters and digits, its length is a single byte on the wire.
+    // see: https://www.rfc-editor.org/rfc/rfc8659#section-4.1
+    fn tag(&mut self) -> Result<String, String> {
+        let token = self.next()?;
+        if token.text.is_empty()
+            || token.text.len() > 255
+            || !token.text.bytes().all(|x| x.is_ascii_alphanumeric())
+        {
+            return Err(format!("bad caa tag: {}", token.text));
+        }
+
+        return Ok(token.text.clone());
+    }
+
+    // fields such as base64 may be split by blanks
+    fn concat_rest(&mut self) -> String {
+        let rest = self
+            .tokens
+            .iter()
+            .map(|token| token.text.as_str())
+            .collect::<Vec<&str>>()
+            .join("");
+        self.tokens = &[];
+
+        return rest;
+    }
+
+    fn types(&mut self) -> Result<Vec<DnsType>, String> {
+        let types = self
+            .tokens
+            .iter()
+            .map(|token| token.text.parse::<DnsType>())
+            .collect();
+        self.tokens = &[];
+
+        return types;
+    }
+
+    fn salt(&mut self) -> Result<Vec<u8>, String> {
+        let token = self.next()?;
+        if token.text == "-" {
+            return Ok(Vec::new());
+        }
+
+        return parse_hex(&token.text);
+    }
+
+    fn end(&self) -> Result<(), String> {
+        return match self.tokens.first() {
+            Some(token) => Err(format!("extra rdata field: {}", token.text)),
+            None => Ok(()),
+        };
+    }
+}
+
+// the generic form works for every type. see: https://www.rfc-editor.org/rfc/rfc3597#section-5
+fn parse_generic_rdata(rr_type: DnsType, fields: &mut Fields) -> Result<RData, String> {
+    let length: usize = fields.parse()?;
+    let data = parse_hex(&fields.concat_rest())?;
+    if data.len() != length {
+        return Err(format!(
+            "rdata length mismatch: {} != {}",
+            data.len(),
+            length
+        ));
+    }
+
+    return parse_dns_rdata(&data, 0, &rr_type, length)
+        .map_err(|error| format!("bad generic rdata: {}", error));
+}
+
+fn parse_rdata(rr_type: DnsType, fields: &mut Fields) -> Result<RData, String> {
+    if fields
+        .tokens
+        .first()
+        .is_some_and(|token| token.text == "\\#" && !token.quoted)
+    {
+        fields.next()?;
+        return parse_generic_rdata(rr_type, fields);
+    }
+
+    let rdata = match rr_type {
+        DnsType::A => RData::A(fields.parse()?),
+        DnsType::NS => RData::NS(fields.name()?),
+        DnsType::CNAME => RData::CNAME(fields.name()?),
+        DnsType::SOA => RData::SOA {
+            mname: fields.name()?,
+            rname: fields.name()?,
+            serial: fields.parse()?,
+            refresh: parse_ttl(&fields.next()?.text)?,
+            retry: parse_ttl(&fields.next()?.text)?,
+            expire: parse_ttl(&fields.next()?.text)?,
+            minimum: parse_ttl(&fields.next()?.text)?,
+        },
+        DnsType::PTR => RData::PTR(fields.name()?),
+        DnsType::MX => RData::MX {
+            preference: fields.parse()?,
+            exchange: fields.name()?,
+        },
+        DnsType::TXT => {
+            let mut strings = vec![parse_character_string(fields.next()?)?];
+            while let Ok(token) = fields.next() {
+                strings.push(parse_character_string(token)?);
+            }
+            RData::TXT(strings)
+        }
+        DnsType::AAAA => RData::AAAA(fields.parse()?),
+        DnsType::SRV => RData::SRV {
+            priority: fields.parse()?,
+            weight: fields.parse()?,
+            port: fields.parse()?,
+            target: fields.name()?,
+        },
+        DnsType::CAA => RData::CAA {
+            flags: fields.parse()?,
+            tag: fields.tag()?,
+            value: parse_character_string(fields.next()?)?,
+        },
+        DnsType::DS => RData::DS {
+            key_tag: fields.parse()?,
+            algorithm: fields.parse()?,
+            digest_type: fields.parse()?,
+            digest: parse_hex(&fields.concat_rest())?,
+        },
+        DnsType::RRSIG => RData::RRSIG {
+            type_covered: fields.parse()?,
+            algorithm: fields.parse()?,
+            labels: fields.parse()?,
+            original_ttl: parse_ttl(&fields.next()?.text)?,
+            expiration: parse_dnssec_time(&fields.next()?.text)?,
+            inception: parse_dnssec_time(&fields.next()?.text)?,
+            key_tag: fields.parse()?,
+            signer_name: fields.name()?,
+            signature: parse_base64(&fields.concat_rest())?,
+        },
+        DnsType::NSEC => RData::NSEC {
+            next_domain_name: fields.name()?,
+            types: fields.types()?,
+        },
+        DnsType::DNSKEY => RData::DNSKEY {
+            flags: fields.parse()?,
+            protocol: fields.parse()?,
+            algorithm: fields.parse()?,
+            public_key: parse_base64(&fields.concat_rest())?,
+        },
+        DnsType::NSEC3 => RData::NSEC3 {
+            hash_algorithm: fields.parse()?,
+            flags: fields.parse()?,
+            iterations: fields.parse()?,
+            salt: fields.salt()?,
+            next_hashed_owner_name: parse_base32hex(&fields.next()?.text)?,
+            types: fields.types()?,
+        },
+        DnsType::NSEC3PARAM => RData::NSEC3PARAM {
+            hash_algorithm: fields.parse()?,
+            flags: fields.parse()?,
+            iterations: fields.parse()?,
+            salt: fields.salt()?,
+        },
+        DnsType::OPT | DnsType::ANY => {
+            return Err(format!("{} is not allowed in a zone", rr_type));
+        }
+        DnsType::Unknown(_) => return Err("unknown types need the \\# form".to_string()),
+    };
+    fields.end()?;
+
+    return Ok(rdata);
+}
+
+// rdata in presentation format on its own, as other encodings carry it
+pub(super) fn parse_rdata_str(
+    rr_type: DnsType,
+    rdata_str: &str,
+    origin: &DomainName,
+) -> Result<RData, String> {
+    let tokens = tokenize(rdata_str)
+        .map_err(|(_, message)| message)?
+        .into_iter()
+        .flat_map(|entry| entry.tokens)
+        .collect::<Vec<Token>>();
+
+    return parse_rdata(
+        rr_type,
+        &mut Fields {
+            tokens: &tokens,
+            origin,
+        },
+    );
+}
+
+// what carries over from one entry to the next
+struct ZoneParser {
+    origin: DomainName,
+    default_ttl: Option<u32>,
+    last_owner: Option<DomainName>,
+    last_ttl: Option<u32>,
+    last_class: DnsClass,
+    records: Vec<DnsResourceRecord>,
+}
+impl ZoneParser {
+    fn parse_text(&mut self, text: &str, file: &str, depth: usize) -> Result<(), ZoneParseError> {
+        let create_error = |line, message| ZoneParseError {
+            file: file.to_string(),
+            line,
+            message,
+        };
+
+        let entries = tokenize(text).map_err(|(line, message)| create_error(line, message))?;
+        for entry in entries {
+            if !entry.blank_owner
+                && entry.tokens[0].text.starts_with('$')
+                && !entry.tokens[0].quoted
+            {
+                self.parse_directive(&entry, file, depth)?;
+                continue;
+            }
+
+            let record = self
+                .parse_record(&entry)
+                .map_err(|message| create_error(entry.line, message))?;
+            self.records.push(record);
+        }
+
+        return Ok(());
+    }
+
+    fn parse_directive(
+        &mut self,
+        entry: &Entry,
+        file: &str,
+        depth: usize,
+    ) -> Result<(), ZoneParseError> {
+        let create_error = |message| ZoneParseError {
+            file: file.to_string(),
+            line: entry.line,
+            message,
+        };
+        let arguments: Vec<&str> = entry.tokens[1..]
+            .iter()
+            .map(|token| token.text.as_str())
+            .collect();
+
+        match (
+            entry.tokens[0].text.to_ascii_uppercase().as_str(),
+            arguments.as_slice(),
+        ) {
+            ("$ORIGIN", [origin]) => {
+                self.origin = parse_name(origin, &self.origin).map_err(create_error)?;
+            }
+            // see: https://www.rfc-editor.org/rfc/rfc2308#section-4
+            ("$TTL", [ttl]) => {
+                self.default_ttl = Some(parse_ttl(ttl).map_err(create_error)?);
+            }
+            ("$INCLUDE", [path] | [path, _]) => {
+                if depth >= MAX_INCLUDE_DEPTH {
+                    return Err(create_error(format!("too deep $INCLUDE: {}", path)));
+                }
+
+                // relative to the including file
+                let path = Path::new(file).parent().unwrap_or(Path::new("")).join(path);
+                let text = fs::read_to_string(&path)
+                    .map_err(|error| create_error(format!("{}: {}", path.display(), error)))?;
+
+                // the origin and the owner are restored after the file
+                let origin = self.origin.clone();
+                let last_owner = self.last_owner.clone();
+                if let [_, include_origin] = arguments.as_slice() {
+                    self.origin = parse_name(include_origin, &origin).map_err(create_error)?;
+                }
+                self.parse_text(&text, &path.to_string_lossy(), depth + 1)?;
+                self.origin = origin;
+                self.last_owner = last_owner;
+            }
+            (directive, _) => {
+                return Err(create_error(format!("bad directive: {}", directive)));
+            }
+        }
+
+        return Ok(());
+    }
+
+    fn parse_record(&mut self, entry: &Entry) -> Result<DnsResourceRecord, String> {
+        let mut tokens = entry.tokens.as_slice();
+
+        let name = if entry.blank_owner {
+            self.last_owner
+                .clone()
+                .ok_or("no previous owner name".to_string())?
+        } else {
+            let (owner, rest) = tokens.split_first().unwrap();
+            tokens = rest;
+            parse_name(&owner.text, &self.origin)?
+        };
+
+        // ttl and class come in either order, and each may be left out
+        let mut ttl = None;
+        let mut class = None;
+        let rr_type = loop {
+            let (token, rest) = tokens.split_first().ok_or("missing type".to_string())?;
+            tokens = rest;
+
+            if ttl.is_none() && token.text.starts_with(|x: char| x.is_ascii_digit()) {
+                ttl = Some(parse_ttl(&token.text)?);
+            } else if class.is_none()
+                && let Ok(rr_class) = token.text.parse::<DnsClass>()
+            {
+                class = Some(rr_class);
+            } else {
+                break token.text.parse::<DnsType>()?;
+            }
+        };
+
+        let rr_class = class.unwrap_or(self.last_class);
+        let ttl = ttl
+            .or(self.default_ttl)
+            .or(self.last_ttl)
+            .ok_or("no ttl and no $TTL".to_string())?;
+        let rdata = parse_rdata(
+            rr_type,
+            &mut Fields {
+                tokens,
+                origin: &self.origin,
+            },
+        )?;
+
+        self.last_owner = Some(name.clone());
+        self.last_ttl = Some(ttl);
+        self.last_class = rr_class;
+
+        return Ok(DnsResourceRecord {
+            name,
+            rr_type,
+            rr_class,
+            ttl,
+            rdata,
+        });
+    }
+}
+
+/// Parses a zone master file. The file name is used for errors and to find `$INCLUDE` files.
+///
+/// ```
+/// use rust_dns::dns::*;
+///
+/// let text = "$TTL 1h\n@ NS ns\nns A 192.0.2.1\n   AAAA 2001:db8::1\n";
+/// let records = parse_zone(text, "example.zone", &"example.com.".parse().unwrap()).unwrap();
+/// assert_eq!(records[2].to_string(), "ns.example.com.\t3600\tIN\tAAAA\t2001:db8::1");
+///
+/// let error = parse_zone("a A 192.0.2.1\n", "example.zone", &DomainName::root()).unwrap_err();
+/// assert_eq!(error.to_string(), "example.zone:1: no ttl and no $TTL");
+/// ```
+pub fn parse_zone(
+    text: &str,
+    file: &str,
+    origin: &DomainName,
+) -> Result<Vec<DnsResourceRecord>, ZoneParseError> {
+    let mut parser = ZoneParser {
+        origin: origin.clone(),
+        default_ttl: None,
+        last_owner: None,
+        last_ttl: None,
+        last_class: DnsClass::IN,
+        records: Vec::new(),
+    };
+    parser.parse_text(text, file, 0)?;
+
+    return Ok(parser.records);
+}
+
+/// Reads and parses a zone master file, see [`parse_zone`].
+pub fn parse_zone_file(
+    path: &Path,
+    origin: &DomainName,
+) -> Result<Vec<DnsResourceRecord>, ZoneParseError> {
+    let text = fs::read_to_string(path).map_err(|error| ZoneParseError {
+        file: path.to_string_lossy().to_string(),
+        line: 0,
+        message: error.to_string(),
+    })?;
+
+    return parse_zone(&text, &path.to_string_lossy(), origin);
+}
+#[cfg(test)]
+mod parse_zone {
+    use std::{
+        env, fs,
+        net::{Ipv4Addr, Ipv6Addr},
+    };
+
+    use crate::dns::{
+        error::ZoneParseError,
+        model::{DnsClass, DnsResourceRecord, DnsType, RData},
+        name::DomainName,
+        zone::parse_zone,
+    };
+
+    fn name(name_str: &str) -> DomainName {
+        return name_str.parse().unwrap();
+    }
+
+    #[test]
+    fn case1() {
+        let text = "$ORIGIN example.com.
+$TTL 1h
+@   IN  SOA ns1 hostmaster (
+            2024010101 ; serial
+            2h 1h 2w 5m )
+        NS  ns1
+    600 NS  ns2.example.net.
+ns1 A   192.0.2.1
+    AAAA 2001:db8::1
+www CH 30 TXT \"hello world\" \"a\\\"b\\059\"
+mail MX 10 @
+";
+        let records = parse_zone(text, "example.com.zone", &DomainName::root()).unwrap();
+
+        assert_eq!(
+            records,
+            vec![
+                DnsResourceRecord {
+                    name: name("example.com."),
+                    rr_type: DnsType::SOA,
+                    rr_class: DnsClass::IN,
+                    ttl: 3600,
+                    rdata: RData::SOA {
+                        mname: name("ns1.example.com."),
+                        rname: name("hostmaster.example.com."),
+                        serial: 2024010101,
+                        refresh: 7200,
+                        retry: 3600,
+                        expire: 1209600,
+                        minimum: 300,
+                    },
+                },
+                DnsResourceRecord {
+                    name: name("example.com."),
+                    rr_type: DnsType::NS,
+                    rr_class: DnsClass::IN,
+                    ttl: 3600,
+                    rdata: RData::NS(name("ns1.example.com.")),
+                },
+                DnsResourceRecord {
+                    name: name("example.com."),
+                    rr_type: DnsType::NS,
+                    rr_class: DnsClass::IN,
+                    ttl: 600,
+                    rdata: RData::NS(name("ns2.example.net.")),
+                },
+                DnsResourceRecord {
+                    name: name("ns1.example.com."),
+                    rr_type: DnsType::A,
+                    rr_class: DnsClass::IN,
+                    ttl: 3600,
+                    rdata: RData::A(Ipv4Addr::new(192, 0, 2, 1)),
+                },
+                DnsResourceRecord {
+                    name: name("ns1.example.com."),
+                    rr_type: DnsType::AAAA,
+                    rr_class: DnsClass::IN,
+                    ttl: 3600,
+                    rdata: RData::AAAA("2001:db8::1".parse::<Ipv6Addr>().unwrap()),
+                },
+                DnsResourceRecord {
+                    name: name("www.example.com."),
+                    rr_type: DnsType::TXT,
+                    rr_class: DnsClass::CH,
+                    ttl: 30,
+                    rdata: RData::TXT(vec![b"hello world".to_vec(), b"a\"b;".to_vec()]),
+                },
+                // the class carries over from the previous record
+                DnsResourceRecord {
+                    name: name("mail.example.com."),
+                    rr_type: DnsType::MX,
+                    rr_class: DnsClass::CH,
+                    ttl: 3600,
+                    rdata: RData::MX {
+                        preference: 10,
+                        exchange: name("example.com."),
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn case2() {
+        // without $TTL the last explicit ttl is used
+        let text = "a 300 IN A 192.0.2.1\nb A 192.0.2.2\n";
+        let records = parse_zone(text, "-", &name("example.")).unwrap();
+
+        assert_eq!(records[1].name, name("b.example."));
+        assert_eq!(records[1].ttl, 300);
+    }
+
+    #[test]
+    fn case3() {
+        // from rfc4034 2.3, rfc4034 5.4 and rfc3597 5
+        let text = "example.com. 86400 IN DNSKEY 256 3 5 ( AQPSKmynfzW4kyBv015MUG2DeIQ3
+                 Cbl+BBZH4b/0PY1kxkmvHjcZc8no kfzj31GajIQKY+5CptLr3buXA10h
+                 WqTkF7H6RfoRqXQeogmMHfpftf6z Mv1LyBUgia7za6ZEzOJBOztyvhjL
+                 742iU/TpPSEDhm2SNKLijfUppn1U aNvv4w==  )
+dskey.example.com. 86400 IN DS 60485 5 1 ( 2BB183AF5F22588179A53B0A
+                                           98631FAD1A292118 )
+host.example.com. 86400 IN RRSIG A 5 3 86400 20030322173103 (
+                                 20030220173103 2642 example.com.
+                                 oJB1W6WNGv+ldvQ3WDG0MQkg5IEhjRip8WTr
+                                 J5D6fwFm8nN+6pBzeDQfsS3Ap3o= )
+a.example. 3600 CLASS32 TYPE731 \\# 6 abcd (
+           ef 01 23 45 )
+b.example. 3600 HS A \\# 4 0A000001
+";
+        let records = parse_zone(text, "-", &DomainName::root()).unwrap();
+
+        assert_eq!(records.len(), 5);
+        assert!(matches!(
+            &records[0].rdata,
+            RData::DNSKEY { flags: 256, protocol: 3, algorithm: 5, public_key } if public_key.len() == 130
+        ));
+        assert_eq!(
+            records[1].rdata.to_string(),
+            "60485 5 1 2BB183AF5F22588179A53B0A98631FAD1A292118"
+        );
+        assert!(matches!(
+            &records[2].rdata,
+            RData::RRSIG {
+                type_covered: DnsType::A,
+                expiration: 1048354263,
+                key_tag: 2642,
+                ..
+            }
+        ));
+        assert_eq!(records[3].rr_class, DnsClass::Unknown(32));
+        assert_eq!(records[3].rr_type, DnsType::Unknown(731));
+        assert_eq!(
+            records[3].rdata,
+            RData::Unknown(vec![0xab, 0xcd, 0xef, 0x01, 0x23, 0x45])
+        );
+        assert_eq!(records[4].rdata, RData::A(Ipv4Addr::new(10, 0, 0, 1)));
+    }
+
+    #[test]
+    fn case4() {
+        let error = |text: &str| parse_zone(text, "bad.zone", &name("example.")).unwrap_err();
+
+        assert_eq!(
+            error("$TTL 60\na A 192.0.2.1\n\nb A 192.0.2.300\n"),
+            ZoneParseError {
+                file: "bad.zone".to_string(),
+                line: 4,
+                message: "bad rdata field: 192.0.2.300".to_string(),
+            }
+        );
+        assert_eq!(error("a A 192.0.2.1\n").message, "no ttl and no $TTL");
+        assert_eq!(error(" 60 A 192.0.2.1\n").message, "no previous owner name");
+        assert_eq!(
+            error("a 60 A 192.0.2.1 1\n").message,
+            "extra rdata field: 1"
+        );
+        assert_eq!(
+            error("a 60 TYPE731 00\n").message,
+            "unknown types need the \\# form"
+        );
+        // character strings and CAA tags have a single length byte
+        assert_eq!(
+            error(&format!("a 60 TXT {}\n", "x".repeat(256))).message,
+            format!("too long character string: {}", "x".repeat(256))
+        );
+        assert_eq!(
+            error(&format!("a 60 CAA 0 {} \"x\"\n", "x".repeat(256))).message,
+            format!("bad caa tag: {}", "x".repeat(256))
+        );
+        assert_eq!(
+            error("a 60 CAA 0 is-sue \"x\"\n").message,
+            "bad caa tag: is-sue"
+        );
+        assert_eq!(error("$TTL 60\n$FOO\n").line, 2);
+        assert_eq!(error("$TTL 60\na A (\n192.0.2.1\n").line, 4);
+    }
+
+    #[test]
+    fn case5() {
+        let dir = env::temp_dir().join(format!("rust-dns-zone-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("hosts.zone"),
+            "$TTL 60\nhost A 192.0.2.1\n\nbroken A\n",
+        )
+        .unwrap();
+        let main_path = dir.join("main.zone");
+        let main_zone = "$ORIGIN example.\n$TTL 300\n@ NS ns\n$INCLUDE hosts.zone sub.example.\n";
+
+        let result = parse_zone(main_zone, &main_path.to_string_lossy(), &DomainName::root());
+        let error = result.unwrap_err();
+        assert_eq!(error.file, dir.join("hosts.zone").to_string_lossy());
+        assert_eq!(error.line, 4);
+        assert_eq!(error.message, "missing rdata field");
+
+        fs::write(dir.join("hosts.zone"), "host A 192.0.2.1\n").unwrap();
+        let records = parse_zone(
+            &format!("{}www A 192.0.2.2\n", main_zone),
+            &main_path.to_string_lossy(),
+            &DomainName::root(),
+        )
+        .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        // the origin is restored after the included file
+        assert_eq!(records[1].name, name("host.sub.example."));
+        assert_eq!(records[2].name, name("www.example."));
+    }
+}
@@ -0,0 +1,21 @@
+//! A DNS library with an iterative resolver that speaks to the network through a tap device.
+//!
+//! - [`dns`]: the message model, wire and presentation formats, zone files and DNSSEC
+//! - [`net`]: ethernet, ARP, IPv4 and UDP packets
+//! - [`resolver`]: iterative resolution from the root servers
+//!
+//! ```
+//! use rust_dns::dns::*;
+//!
+//! let name: DomainName = "example.com.".parse().unwrap();
+//! let query = QueryBuilder::new(1)
+//!     .question(&name, DnsType::A, DnsClass::IN)
+//!     .rd(true)
+//!     .build();
+//!
+//! let message = parse_dns_message(&query.to_wire().unwrap()).unwrap();
+//! assert_eq!(message.questions[0].q_name, name);
+//! ```
+pub mod dns;
+pub mod net;
+pub mod resolver;
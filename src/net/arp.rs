@@ -1,4 +1,6 @@
-use crate::{ether::get_ethernet_frame_data, util::*};
+#![allow(clippy::needless_return)]
+
+use super::{ether::get_ethernet_frame_data, util::*};
 
 pub fn create_arp_request_message(
     sender_ipaddr_str: &str,
@@ -60,6 +62,10 @@ pub fn create_arp_reply_message(
 }
 
 pub fn is_arp_request(frame: &Vec<u8>, my_ipaddr: &str, my_macaddr: &str) -> bool {
+    // a whole arp message
+    if frame.len() < 14 + 28 {
+        return false;
+    }
     // destination is broadcast or my mac address
     if !(frame[0..6] == parse_macaddr("ff:ff:ff:ff:ff:ff")
         || frame[0..6] == parse_macaddr(my_macaddr))
@@ -71,14 +77,14 @@ pub fn is_arp_request(frame: &Vec<u8>, my_ipaddr: &str, my_macaddr: &str) -> boo
         return false;
     }
 
-    let message = get_ethernet_frame_data(&frame);
+    let message = get_ethernet_frame_data(frame);
     // opecode is request
     if message[6..6 + 2] != vec![0x00, 0x01] {
         return false;
     };
 
     // target protocol address is my ip address
-    if message[24..24 + 4] != parse_ipaddr(&my_ipaddr) {
+    if message[24..24 + 4] != parse_ipaddr(my_ipaddr) {
         return false;
     }
 
@@ -86,6 +92,10 @@ pub fn is_arp_request(frame: &Vec<u8>, my_ipaddr: &str, my_macaddr: &str) -> boo
 }
 
 pub fn is_arp_reply(frame: &Vec<u8>, my_ipaddr: &str, my_macaddr: &str) -> bool {
+    // a whole arp message
+    if frame.len() < 14 + 28 {
+        return false;
+    }
     // destination is my mac address
     if frame[0..6] != parse_macaddr(my_macaddr) {
         return false;
@@ -95,7 +105,7 @@ pub fn is_arp_reply(frame: &Vec<u8>, my_ipaddr: &str, my_macaddr: &str) -> bool
         return false;
     }
 
-    let message = get_ethernet_frame_data(&frame);
+    let message = get_ethernet_frame_data(frame);
     // opecode is reply
     if message[6..6 + 2] != vec![0x00, 0x02] {
         return false;
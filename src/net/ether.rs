@@ -1,5 +1,8 @@
-use crate::util::*;
+#![allow(clippy::needless_return)]
 
+use super::util::*;
+
+#[allow(clippy::ptr_arg)]
 pub fn create_ethernet_frame(
     protocol_type: u16,
     dest_macaddr_str: &str,
@@ -29,6 +32,7 @@ pub fn create_ethernet_frame(
     .concat();
 }
 
+#[allow(clippy::ptr_arg)]
 pub fn get_ethernet_frame_data(frame: &Vec<u8>) -> Vec<u8> {
     return frame[14..].to_vec();
 }
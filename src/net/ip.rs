@@ -1,6 +1,9 @@
-use crate::util::*;
+#![allow(clippy::needless_return)]
+
+use super::util::*;
 use num::FromPrimitive;
 
+#[allow(clippy::ptr_arg)]
 pub fn create_ip_packet(
     protocol: u8,
     id: u16,
@@ -54,8 +57,30 @@ pub fn create_ip_packet(
     .concat();
 }
 
-pub fn get_ip_packet_data(packet: &Vec<u8>) -> Vec<u8> {
+// none when the header length or the total length does not fit the packet
+pub fn get_ip_packet_data(packet: &[u8]) -> Option<Vec<u8>> {
+    if packet.len() < 20 {
+        return None;
+    }
     let internet_header_length = ((packet[0] & 0x0f) << 2) as usize;
     let total_length = u16::from_be_bytes([packet[2], packet[3]]) as usize;
-    return packet[internet_header_length..total_length].to_vec();
+    if internet_header_length < 20
+        || total_length < internet_header_length
+        || total_length > packet.len()
+    {
+        return None;
+    }
+    return Some(packet[internet_header_length..total_length].to_vec());
+}
+
+pub fn get_ip_packet_protocol(packet: &[u8]) -> u8 {
+    return packet[9];
+}
+
+pub fn get_ip_packet_src_ipaddr(packet: &[u8]) -> String {
+    return print_ipaddr(&packet[12..12 + 4].to_vec());
+}
+
+pub fn get_ip_packet_dest_ipaddr(packet: &[u8]) -> String {
+    return print_ipaddr(&packet[16..16 + 4].to_vec());
 }
@@ -0,0 +1,24 @@
+//! Hand-built ethernet, ARP, IPv4 and UDP packets, as sent and received on a tap device.
+//!
+//! ```
+//! use rust_dns::net::*;
+//!
+//! let datagram = create_udp_datagram(12345, 53, &vec![0xab, 0xcd], "192.0.2.2", "192.0.2.53");
+//! let packet = create_ip_packet(17, 1, "192.0.2.2", "192.0.2.53", &datagram);
+//! let frame = create_ethernet_frame(0x0800, "00:00:5e:00:53:02", "00:00:5e:00:53:01", &packet);
+//!
+//! let received = get_ip_packet_data(&get_ethernet_frame_data(&frame))
+//!     .and_then(|datagram| get_udp_datagram_data(&datagram));
+//! assert_eq!(received, Some(vec![0xab, 0xcd]));
+//! ```
+mod arp;
+mod ether;
+mod ip;
+mod udp;
+mod util;
+
+pub use self::arp::*;
+pub use self::ether::*;
+pub use self::ip::*;
+pub use self::udp::*;
+pub use self::util::*;
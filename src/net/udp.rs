@@ -1,6 +1,9 @@
-use crate::util::*;
+#![allow(clippy::needless_return)]
+
+use super::util::*;
 use num::FromPrimitive;
 
+#[allow(clippy::ptr_arg)]
 pub fn create_udp_datagram(
     src_port_num: u16,
     dest_port_num: u16,
@@ -44,7 +47,22 @@ pub fn create_udp_datagram(
     return [src_port, dest_port, length, checksum, data.clone()].concat();
 }
 
-pub fn get_udp_datagram_data(datagram: &Vec<u8>) -> Vec<u8> {
+// none when the length does not fit the datagram
+pub fn get_udp_datagram_data(datagram: &[u8]) -> Option<Vec<u8>> {
+    if datagram.len() < 8 {
+        return None;
+    }
     let length = u16::from_be_bytes([datagram[4], datagram[5]]) as usize;
-    return datagram[8..length].to_vec();
+    if length < 8 || length > datagram.len() {
+        return None;
+    }
+    return Some(datagram[8..length].to_vec());
+}
+
+pub fn get_udp_datagram_src_port(datagram: &[u8]) -> u16 {
+    return u16::from_be_bytes([datagram[0], datagram[1]]);
+}
+
+pub fn get_udp_datagram_dest_port(datagram: &[u8]) -> u16 {
+    return u16::from_be_bytes([datagram[2], datagram[3]]);
 }
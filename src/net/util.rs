@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return)]
+
 pub fn parse_macaddr(str: &str) -> Vec<u8> {
     let vec = str
         .split(':')
@@ -13,7 +15,7 @@ pub fn parse_macaddr(str: &str) -> Vec<u8> {
 
 #[cfg(test)]
 mod parse_macaddr {
-    use crate::util::*;
+    use crate::net::util::*;
 
     #[test]
     fn case1() {
@@ -36,6 +38,7 @@ mod parse_macaddr {
     }
 }
 
+#[allow(clippy::ptr_arg)]
 pub fn print_macaddr(addr: &Vec<u8>) -> String {
     if addr.len() != 6 {
         panic!("invalid hwaddr length: {}", addr.len());
@@ -50,7 +53,7 @@ pub fn print_macaddr(addr: &Vec<u8>) -> String {
 
 #[cfg(test)]
 mod print_macaddr {
-    use crate::util::*;
+    use crate::net::util::*;
 
     #[test]
     fn case1() {
@@ -64,7 +67,7 @@ mod print_macaddr {
 pub fn parse_ipaddr(str: &str) -> Vec<u8> {
     let vec = str
         .split('.')
-        .map(|str| u8::from_str_radix(str, 10).unwrap())
+        .map(|str| str.parse::<u8>().unwrap())
         .collect::<Vec<u8>>();
 
     if vec.len() != 4 {
@@ -76,7 +79,7 @@ pub fn parse_ipaddr(str: &str) -> Vec<u8> {
 
 #[cfg(test)]
 mod parse_ipaddr {
-    use crate::util::*;
+    use crate::net::util::*;
 
     #[test]
     fn case1() {
@@ -96,6 +99,7 @@ mod parse_ipaddr {
     }
 }
 
+#[allow(clippy::ptr_arg)]
 pub fn print_ipaddr(addr: &Vec<u8>) -> String {
     if addr.len() != 4 {
         panic!("invalid ipaddr length: {}", addr.len());
@@ -110,7 +114,7 @@ pub fn print_ipaddr(addr: &Vec<u8>) -> String {
 
 #[cfg(test)]
 mod print_ipaddr {
-    use crate::util::*;
+    use crate::net::util::*;
 
     #[test]
     fn case1() {
@@ -119,6 +123,7 @@ mod print_ipaddr {
 }
 
 // see: https://docs.google.com/presentation/d/1kjvdsM2Slfug4t4lQ5HRCitoTbH7edl-1OPtEaG53D8/edit#slide=id.gd326ebd0a8_0_798
+#[allow(clippy::ptr_arg)]
 pub fn checksum16(data: &Vec<u8>, init: u32) -> u16 {
     let mut sum = init;
     let mut index = 0;
@@ -140,7 +145,7 @@ pub fn checksum16(data: &Vec<u8>, init: u32) -> u16 {
 
 #[cfg(test)]
 mod checksum16 {
-    use crate::util::*;
+    use crate::net::util::*;
 
     #[test]
     fn case1() {
@@ -168,6 +173,7 @@ mod checksum16 {
 }
 
 #[allow(dead_code)]
+#[allow(clippy::ptr_arg)]
 pub fn dump_vec(data: &Vec<u8>) {
     println!(
         "{}",
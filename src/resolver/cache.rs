@@ -0,0 +1,350 @@
+#![allow(clippy::needless_return)]
+
+use std::collections::HashMap;
+
+use crate::dns::*;
+
+// the keys of the zone a server serves. Err holds the status every answer from the zone gets,
+// as when validation is disabled or the zone is not signed.
+pub(super) type Trust = Result<ZoneKeys, ValidationStatus>;
+
+// a zone and where to ask about names in it, learned from a referral
+#[derive(PartialEq, Debug, Clone)]
+pub(super) struct Delegation {
+    pub zone: DomainName,
+    // name servers without glue, resolved only when every address failed
+    pub name_servers: Vec<DomainName>,
+    // addresses of the name servers, from the glue
+    pub addresses: Vec<String>,
+    pub trust: Trust,
+}
+
+// the records of an RRset and how they validated
+type RRset = (Vec<DnsResourceRecord>, ValidationStatus);
+
+#[derive(Debug, Clone)]
+struct CacheEntry<T> {
+    value: T,
+    stored_at: u32,
+    expires_at: u32,
+}
+impl<T: Clone> CacheEntry<T> {
+    fn new(value: T, ttl: u32, now: u32) -> Self {
+        return CacheEntry {
+            value,
+            stored_at: now,
+            expires_at: now.saturating_add(ttl),
+        };
+    }
+
+    fn is_expired(&self, now: u32) -> bool {
+        return now >= self.expires_at;
+    }
+}
+
+// RRsets and delegations, each kept as long as the smallest TTL among its records, and names
+// or types that do not exist.
+// see: https://www.rfc-editor.org/rfc/rfc2181#section-5.2
+pub(super) struct Cache {
+    rrsets: HashMap<(DomainName, DnsType), CacheEntry<RRset>>,
+    delegations: HashMap<DomainName, CacheEntry<Delegation>>,
+    // the type is None for NXDOMAIN, which holds for every type of the name
+    negatives: HashMap<(DomainName, Option<DnsType>), CacheEntry<ValidationStatus>>,
+    // validated DNSKEY RRsets by zone
+    zone_keys: HashMap<DomainName, CacheEntry<ZoneKeys>>,
+}
+impl Cache {
+    pub fn new() -> Self {
+        return Cache {
+            rrsets: HashMap::new(),
+            delegations: HashMap::new(),
+            negatives: HashMap::new(),
+            zone_keys: HashMap::new(),
+        };
+    }
+
+    pub fn insert_rrset(
+        &mut self,
+        records: &[DnsResourceRecord],
+        status: ValidationStatus,
+        now: u32,
+    ) {
+        let Some(first) = records.first() else {
+            return;
+        };
+        let ttl = records.iter().map(|rr| rr.ttl).min().unwrap();
+
+        self.rrsets.insert(
+            (first.name.clone(), first.rr_type),
+            CacheEntry::new((records.to_vec(), status), ttl, now),
+        );
+    }
+
+    // the TTLs of the records count down from when they were stored
+    pub fn get_rrset(&mut self, name: &DomainName, rr_type: DnsType, now: u32) -> Option<RRset> {
+        let key = (name.clone(), rr_type);
+        let entry = self.rrsets.get(&key)?;
+        if entry.is_expired(now) {
+            self.rrsets.remove(&key);
+            return None;
+        }
+
+        let (records, status) = &entry.value;
+        // a clock that stepped backwards counts as no time elapsed
+        let elapsed = now.saturating_sub(entry.stored_at);
+        let records = records
+            .iter()
+            .map(|rr| DnsResourceRecord {
+                ttl: rr.ttl.saturating_sub(elapsed),
+                ..rr.clone()
+            })
+            .collect();
+
+        return Some((records, *status));
+    }
+
+    // NXDOMAIN or NODATA. see: https://www.rfc-editor.org/rfc/rfc2308#section-5
+    pub fn insert_negative(
+        &mut self,
+        name: &DomainName,
+        rr_type: DnsType,
+        rcode: DnsHeaderRcode,
+        status: ValidationStatus,
+        ttl: u32,
+        now: u32,
+    ) {
+        let rr_type = match rcode {
+            DnsHeaderRcode::NoNameError => None,
+            _ => Some(rr_type),
+        };
+
+        self.negatives
+            .insert((name.clone(), rr_type), CacheEntry::new(status, ttl, now));
+    }
+
+    // NoNameError when the name does not exist, NoError when only the type does not
+    pub fn get_negative(
+        &mut self,
+        name: &DomainName,
+        rr_type: DnsType,
+        now: u32,
+    ) -> Option<(DnsHeaderRcode, ValidationStatus)> {
+        for (key, rcode) in [
+            ((name.clone(), None), DnsHeaderRcode::NoNameError),
+            ((name.clone(), Some(rr_type)), DnsHeaderRcode::NoError),
+        ] {
+            let Some(entry) = self.negatives.get(&key) else {
+                continue;
+            };
+            if entry.is_expired(now) {
+                self.negatives.remove(&key);
+                continue;
+            }
+
+            return Some((rcode, entry.value));
+        }
+
+        return None;
+    }
+
+    pub fn insert_delegation(&mut self, delegation: Delegation, ttl: u32, now: u32) {
+        self.delegations.insert(
+            delegation.zone.clone(),
+            CacheEntry::new(delegation, ttl, now),
+        );
+    }
+
+    pub fn insert_zone_keys(&mut self, zone_keys: ZoneKeys, ttl: u32, now: u32) {
+        self.zone_keys
+            .insert(zone_keys.zone.clone(), CacheEntry::new(zone_keys, ttl, now));
+    }
+
+    pub fn get_zone_keys(&mut self, zone: &DomainName, now: u32) -> Option<ZoneKeys> {
+        let entry = self.zone_keys.get(zone)?;
+        if entry.is_expired(now) {
+            self.zone_keys.remove(zone);
+            return None;
+        }
+
+        return Some(entry.value.clone());
+    }
+
+    // the delegation of the closest enclosing zone of the name
+    pub fn get_delegation(&mut self, name: &DomainName, now: u32) -> Option<Delegation> {
+        let mut zone = Some(name.clone());
+        while let Some(current) = zone {
+            if let Some(entry) = self.delegations.get(&current) {
+                if !entry.is_expired(now) {
+                    return Some(entry.value.clone());
+                }
+                self.delegations.remove(&current);
+            }
+            zone = current.parent();
+        }
+
+        return None;
+    }
+}
+#[cfg(test)]
+mod rrset {
+    use crate::{
+        dns::{DnsType, DomainName, ValidationStatus, parse_zone},
+        resolver::cache::Cache,
+    };
+
+    #[test]
+    fn case1() {
+        let records = parse_zone(
+            "www.example.com. 300 A 192.0.2.1\nwww.example.com. 600 A 192.0.2.2\n",
+            "test",
+            &DomainName::root(),
+        )
+        .unwrap();
+        let name: DomainName = "WWW.example.com.".parse().unwrap();
+
+        let mut cache = Cache::new();
+        cache.insert_rrset(&records, ValidationStatus::Insecure, 1000);
+
+        let (cached, status) = cache.get_rrset(&name, DnsType::A, 1100).unwrap();
+        assert_eq!(status, ValidationStatus::Insecure);
+        assert_eq!(
+            cached.iter().map(|rr| rr.ttl).collect::<Vec<u32>>(),
+            vec![200, 500]
+        );
+        assert_eq!(cache.get_rrset(&name, DnsType::AAAA, 1100), None);
+
+        // the whole RRset expires with its smallest TTL
+        assert_eq!(cache.get_rrset(&name, DnsType::A, 1300), None);
+        assert_eq!(cache.get_rrset(&name, DnsType::A, 1100), None);
+    }
+
+    #[test]
+    fn case2() {
+        let records = parse_zone(
+            "www.example.com. 300 A 192.0.2.1\n",
+            "test",
+            &DomainName::root(),
+        )
+        .unwrap();
+        let name: DomainName = "www.example.com.".parse().unwrap();
+
+        let mut cache = Cache::new();
+        cache.insert_rrset(&records, ValidationStatus::Insecure, 1000);
+
+        // the clock stepped backwards
+        let (cached, _) = cache.get_rrset(&name, DnsType::A, 900).unwrap();
+        assert_eq!(cached[0].ttl, 300);
+    }
+}
+#[cfg(test)]
+mod negative {
+    use crate::{
+        dns::{DnsHeaderRcode, DnsType, DomainName, ValidationStatus},
+        resolver::cache::Cache,
+    };
+
+    #[test]
+    fn case1() {
+        let nodata: DomainName = "example.com.".parse().unwrap();
+        let nxdomain: DomainName = "nothing.example.com.".parse().unwrap();
+
+        let mut cache = Cache::new();
+        let status = ValidationStatus::Indeterminate;
+        cache.insert_negative(&nodata, DnsType::MX, DnsHeaderRcode::NoError, status, 60, 0);
+        cache.insert_negative(
+            &nxdomain,
+            DnsType::A,
+            DnsHeaderRcode::NoNameError,
+            status,
+            30,
+            0,
+        );
+
+        assert_eq!(
+            cache.get_negative(&nodata, DnsType::MX, 0),
+            Some((DnsHeaderRcode::NoError, status))
+        );
+        assert_eq!(cache.get_negative(&nodata, DnsType::A, 0), None);
+        // NXDOMAIN holds for every type
+        assert_eq!(
+            cache.get_negative(&nxdomain, DnsType::AAAA, 0),
+            Some((DnsHeaderRcode::NoNameError, status))
+        );
+
+        assert_eq!(cache.get_negative(&nxdomain, DnsType::A, 30), None);
+        assert_eq!(
+            cache.get_negative(&nodata, DnsType::MX, 30),
+            Some((DnsHeaderRcode::NoError, status))
+        );
+        assert_eq!(cache.get_negative(&nodata, DnsType::MX, 60), None);
+    }
+}
+#[cfg(test)]
+mod delegation {
+    use crate::{
+        dns::{DomainName, ValidationStatus},
+        resolver::cache::{Cache, Delegation},
+    };
+
+    #[test]
+    fn case1() {
+        let create_delegation = |zone: &str, address: &str| Delegation {
+            zone: zone.parse().unwrap(),
+            name_servers: vec![format!("ns.{}", zone).parse().unwrap()],
+            addresses: vec![address.to_string()],
+            trust: Err(ValidationStatus::Indeterminate),
+        };
+        let name: DomainName = "www.example.com.".parse().unwrap();
+
+        let mut cache = Cache::new();
+        assert_eq!(cache.get_delegation(&name, 0), None);
+
+        cache.insert_delegation(create_delegation("com.", "192.5.6.30"), 172800, 0);
+        cache.insert_delegation(create_delegation("example.com.", "192.0.2.53"), 3600, 0);
+        assert_eq!(
+            cache.get_delegation(&name, 0).unwrap().addresses,
+            vec!["192.0.2.53"]
+        );
+        assert_eq!(
+            cache
+                .get_delegation(&"example.net.".parse().unwrap(), 0)
+                .map(|delegation| delegation.zone),
+            None
+        );
+
+        // falls back to the enclosing zone once the closest one expires
+        assert_eq!(
+            cache.get_delegation(&name, 3600).unwrap().addresses,
+            vec!["192.5.6.30"]
+        );
+    }
+}
+#[cfg(test)]
+mod zone_keys {
+    use crate::{
+        dns::{DomainName, RData, ZoneKeys},
+        resolver::cache::Cache,
+    };
+
+    #[test]
+    fn case1() {
+        let zone_keys = ZoneKeys {
+            zone: DomainName::root(),
+            dnskeys: vec![RData::DNSKEY {
+                flags: 257,
+                protocol: 3,
+                algorithm: 15,
+                public_key: vec![1; 32],
+            }],
+        };
+
+        let mut cache = Cache::new();
+        cache.insert_zone_keys(zone_keys.clone(), 3600, 0);
+        assert_eq!(
+            cache.get_zone_keys(&DomainName::root(), 3599),
+            Some(zone_keys)
+        );
+        assert_eq!(cache.get_zone_keys(&"com.".parse().unwrap(), 0), None);
+        assert_eq!(cache.get_zone_keys(&DomainName::root(), 3600), None);
+    }
+}
@@ -0,0 +1,101 @@
+#![allow(clippy::needless_return)]
+
+use std::{
+    ops::RangeInclusive,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::dns::{MessageFormat, RData, ROOT_IP_ADDRS, create_root_trust_anchors};
+
+/// Where the resolver writes its progress, one line at a time.
+pub type LogSink = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// The current time in seconds since the epoch, for cache expiry and signature validity.
+pub type Clock = Arc<dyn Fn() -> u32 + Send + Sync>;
+
+/// Settings of a [`Resolver`](super::Resolver). The default matches `scripts/setup_tap.sh`.
+///
+/// ```
+/// use std::sync::Arc;
+/// use rust_dns::resolver::ResolverConfig;
+///
+/// let config = ResolverConfig {
+///     iface_name: "tap1".to_string(),
+///     max_depth: 8,
+///     log: Arc::new(|_| {}),
+///     ..Default::default()
+/// };
+/// assert_eq!(config.retries, 1);
+/// ```
+#[derive(Clone)]
+pub struct ResolverConfig {
+    pub iface_name: String,
+    pub my_ipaddr: String,
+    pub my_macaddr: String,
+    // a random one is used for every query
+    pub source_ports: RangeInclusive<u16>,
+    pub gateway_ipaddr: String,
+    // addresses of the root servers to start from
+    pub root_hints: Vec<String>,
+    // how long to wait for each reply
+    pub timeout: Duration,
+    // how many more times a query is sent to a server that does not reply, before the next
+    // server of the zone is asked
+    pub retries: u16,
+    // how often a server other than the fastest known one is asked first, from 0.0 to 1.0
+    pub exploration: f64,
+    // randomize the case of the names in queries, and accept only replies that echo it.
+    // see: https://datatracker.ietf.org/doc/html/draft-vixie-dnsext-dns0x20-00
+    pub case_randomization: bool,
+    // how many referrals, CNAMEs and name server lookups may be nested
+    pub max_depth: u16,
+    // how many CNAMEs may be followed from one name
+    pub max_cname_chain: u16,
+    // how many referrals may be followed for one name
+    pub max_referrals: u16,
+    // how many queries one resolution may send, retries and name server lookups included
+    pub max_queries: u32,
+    // validate from the trust anchors
+    pub dnssec: bool,
+    // DS records of the root keys the chain of trust starts from
+    pub trust_anchors: Vec<RData>,
+    // how replies are written to the log
+    pub format: MessageFormat,
+    pub log: LogSink,
+    // where replies go instead in the JSON format, one object per line, so that a log pipeline
+    // never sees the other lines
+    pub json_log: LogSink,
+    pub clock: Clock,
+}
+impl Default for ResolverConfig {
+    fn default() -> Self {
+        return ResolverConfig {
+            iface_name: "tap0".to_string(),
+            my_ipaddr: "192.168.70.2".to_string(),
+            my_macaddr: "00:00:5e:00:53:01".to_string(),
+            source_ports: 1024..=65535,
+            gateway_ipaddr: "192.168.70.1".to_string(),
+            root_hints: ROOT_IP_ADDRS.iter().map(|addr| addr.to_string()).collect(),
+            timeout: Duration::from_millis(3000),
+            retries: 1,
+            exploration: 0.05,
+            case_randomization: false,
+            max_depth: 32,
+            max_cname_chain: 8,
+            max_referrals: 16,
+            max_queries: 100,
+            dnssec: false,
+            trust_anchors: create_root_trust_anchors(),
+            format: MessageFormat::Default,
+            log: Arc::new(|line| println!("{}", line)),
+            json_log: Arc::new(|line| println!("{}", line)),
+            clock: Arc::new(|| {
+                return SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs() as u32;
+            }),
+        };
+    }
+}
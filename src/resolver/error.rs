@@ -0,0 +1,37 @@
+use core::fmt;
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum ResolveError {
+    // every server of a zone failed to reply, or replied with an error other than NXDOMAIN
+    ServerFailure(String),
+    // referrals, CNAMEs and name server lookups nested deeper than the config allows
+    MaxDepthExceeded(u16),
+    // a CNAME led to another more times than the config allows, as when two point at each other
+    CnameChainTooLong(u16),
+    // a name was referred down more times than the config allows
+    TooManyReferrals(u16),
+    // more queries were needed to answer than the config allows
+    QueryBudgetExhausted(u32),
+    // the same question was about to be sent to the same server again, or the address of a name
+    // server was needed to resolve itself
+    LoopDetected(String),
+}
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ResolveError::ServerFailure(message) => write!(f, "server failure: {}", message),
+            ResolveError::MaxDepthExceeded(depth) => write!(f, "max depth exceeded: {}", depth),
+            ResolveError::CnameChainTooLong(length) => {
+                write!(f, "cname chain too long: {}", length)
+            }
+            ResolveError::TooManyReferrals(referrals) => {
+                write!(f, "too many referrals: {}", referrals)
+            }
+            ResolveError::QueryBudgetExhausted(queries) => {
+                write!(f, "query budget exhausted: {}", queries)
+            }
+            ResolveError::LoopDetected(message) => write!(f, "loop detected: {}", message),
+        }
+    }
+}
+impl std::error::Error for ResolveError {}
@@ -0,0 +1,101 @@
+#![allow(clippy::needless_return)]
+
+// replies from a script instead of the network, for the tests of the resolver
+
+use std::{cell::RefCell, rc::Rc};
+
+use super::transport::Transport;
+use crate::dns::{
+    DnsClass, DnsHeaderRcode, DnsMessage, DnsQuestionSectionEntry, DomainName, QueryBuilder,
+    parse_zone,
+};
+
+pub struct ScriptedTransport {
+    // by server and question, the id is copied from the query
+    pub replies: Vec<(String, DnsQuestionSectionEntry, DnsMessage)>,
+    // the servers and questions in the order they were asked
+    pub queries: Rc<RefCell<Vec<(String, DnsQuestionSectionEntry)>>>,
+    // the ids of the queries, in the same order
+    pub ids: Rc<RefCell<Vec<u16>>>,
+    // servers that flip the case of every letter of the question instead of echoing it
+    pub case_mangling_servers: Vec<String>,
+}
+
+impl Transport for ScriptedTransport {
+    fn exchange(
+        &self,
+        query: &DnsMessage,
+        server_ipaddr: &str,
+        _exact_case: bool,
+    ) -> Result<DnsMessage, String> {
+        let question = &query.questions[0];
+        self.queries
+            .borrow_mut()
+            .push((server_ipaddr.to_string(), question.clone()));
+        self.ids.borrow_mut().push(query.header.id);
+
+        let (_, _, reply) = self
+            .replies
+            .iter()
+            .find(|(server, reply_question, _)| {
+                server == server_ipaddr && reply_question == question
+            })
+            .ok_or(format!("no reply from {} to {}", server_ipaddr, question))?;
+        let mut reply = reply.clone();
+        reply.header.id = query.header.id;
+        reply.questions = query.questions.clone();
+        if self
+            .case_mangling_servers
+            .iter()
+            .any(|server| server == server_ipaddr)
+        {
+            for question in &mut reply.questions {
+                let flipped = question
+                    .q_name
+                    .to_string()
+                    .chars()
+                    .map(|c| {
+                        if c.is_ascii_uppercase() {
+                            return c.to_ascii_lowercase();
+                        } else {
+                            return c.to_ascii_uppercase();
+                        }
+                    })
+                    .collect::<String>();
+                question.q_name = flipped.parse().unwrap();
+            }
+        }
+
+        return Ok(reply);
+    }
+}
+
+// a reply to "<name> <type>" with the sections written as zone file text
+pub fn scripted_reply(
+    question: &str,
+    rcode: DnsHeaderRcode,
+    answers: &str,
+    authorities: &str,
+    additionals: &str,
+) -> (DnsQuestionSectionEntry, DnsMessage) {
+    let (q_name, q_type) = question.split_once(' ').unwrap();
+    let question = DnsQuestionSectionEntry {
+        q_name: q_name.parse().unwrap(),
+        q_type: q_type.parse().unwrap(),
+        q_class: DnsClass::IN,
+    };
+    let parse = |text: &str| parse_zone(text, "reply", &DomainName::root()).unwrap();
+    let mut message = QueryBuilder::new(0)
+        .question(&question.q_name, question.q_type, question.q_class)
+        .build();
+    message.header.qr = true;
+    message.header.rcode = rcode;
+    message.answers = parse(answers);
+    message.authorities = parse(authorities);
+    message.additionals = parse(additionals);
+    message.header.ancount = message.answers.len() as u16;
+    message.header.nscount = message.authorities.len() as u16;
+    message.header.arcount = message.additionals.len() as u16;
+
+    return (question, message);
+}
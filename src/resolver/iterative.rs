@@ -0,0 +1,2283 @@
+#![allow(clippy::needless_return)]
+
+use std::{cell::RefCell, collections::HashSet, slice, time::Instant};
+
+use rand::random;
+
+use super::{
+    cache::{Cache, Delegation, Trust},
+    config::ResolverConfig,
+    error::ResolveError,
+    servers::ServerTable,
+    transport::{TapTransport, Transport, echoes_case},
+};
+use crate::dns::*;
+
+/// The result of [`Resolver::resolve`].
+#[derive(PartialEq, Debug, Clone)]
+pub struct Answer {
+    pub name: DomainName,
+    pub q_type: DnsType,
+    // NoError, or NoNameError when the name does not exist. No records with NoError means the
+    // name exists without the type (NODATA).
+    pub rcode: DnsHeaderRcode,
+    // the CNAME chain first, then the RRset of the last name
+    pub records: Vec<DnsResourceRecord>,
+    pub status: ValidationStatus,
+}
+impl Answer {
+    /// The answer as a reply from a validating resolver, with AD set when it is secure.
+    pub fn to_message(&self) -> DnsMessage {
+        return DnsMessage {
+            header: DnsHeaderSection {
+                id: 0,
+                qr: true,
+                opcode: DnsHeaderOpecode::Query,
+                aa: false,
+                tc: false,
+                rd: true,
+                ra: true,
+                z: false,
+                ad: self.status == ValidationStatus::Secure,
+                cd: false,
+                rcode: self.rcode,
+                qdcount: 1,
+                ancount: self.records.len() as u16,
+                nscount: 0,
+                arcount: 0,
+            },
+            questions: vec![DnsQuestionSectionEntry {
+                q_name: self.name.clone(),
+                q_type: self.q_type,
+                q_class: DnsClass::IN,
+            }],
+            answers: self.records.clone(),
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+            edns: None,
+        };
+    }
+}
+#[cfg(test)]
+mod answer {
+    use crate::{
+        dns::{DnsHeaderRcode, DnsType, ValidationStatus, parse_zone, print_json_message},
+        resolver::iterative::Answer,
+    };
+
+    #[test]
+    fn case1() {
+        let answer = Answer {
+            name: "www.example.com.".parse().unwrap(),
+            q_type: DnsType::A,
+            rcode: DnsHeaderRcode::NoError,
+            records: parse_zone(
+                "www.example.com. 300 A 192.0.2.80\n",
+                "test",
+                &"example.com.".parse().unwrap(),
+            )
+            .unwrap(),
+            status: ValidationStatus::Secure,
+        };
+
+        let message = answer.to_message();
+        assert!(message.header.ad);
+        assert_eq!(message.answers, answer.records);
+        let json = print_json_message(&message).unwrap();
+        assert!(json.contains(r#""QNAME":"www.example.com.","QTYPE":1"#));
+        assert!(json.contains(r#""ANCOUNT":1"#));
+    }
+}
+
+// how deep a resolution has gone along one chain of lookups, for the limits of the config and
+// the indentation of the log
+#[derive(Clone, Copy, Default)]
+struct Depth {
+    // referrals, CNAMEs and name server lookups nested so far
+    nesting: u16,
+    // CNAMEs followed from the name first asked
+    cnames: u16,
+    // referrals followed for the current name
+    referrals: u16,
+}
+impl Depth {
+    fn indent(&self) -> String {
+        return "    ".repeat(self.nesting as usize);
+    }
+
+    // a name looked up on the way, as the address of a name server, starts a chain of its own
+    fn lookup(self) -> Self {
+        return Depth {
+            nesting: self.nesting + 1,
+            cnames: 0,
+            referrals: 0,
+        };
+    }
+
+    fn cname(self) -> Self {
+        return Depth {
+            nesting: self.nesting + 1,
+            cnames: self.cnames + 1,
+            referrals: 0,
+        };
+    }
+
+    fn referral(self) -> Self {
+        return Depth {
+            nesting: self.nesting + 1,
+            referrals: self.referrals + 1,
+            ..self
+        };
+    }
+}
+
+// what one call of resolve has done so far, across every chain of lookups
+struct Resolution {
+    queries: u32,
+    // the questions sent to each server address
+    asked: HashSet<(DomainName, DnsType, String)>,
+    // the name servers whose addresses are being resolved, the outermost first
+    name_server_lookups: Vec<DomainName>,
+}
+impl Resolution {
+    fn new() -> Self {
+        return Resolution {
+            queries: 0,
+            asked: HashSet::new(),
+            name_server_lookups: Vec::new(),
+        };
+    }
+}
+
+/// An iterative resolver. It starts from the closest delegation it has cached, or from the root
+/// servers, and caches the RRsets and delegations it learns until their TTLs run out.
+///
+/// ```no_run
+/// use rust_dns::{dns::*, resolver::*};
+///
+/// let resolver = Resolver::new(ResolverConfig::default()).unwrap();
+/// let answer = resolver.resolve(&"example.com.".parse().unwrap(), DnsType::AAAA).unwrap();
+/// for record in &answer.records {
+///     println!("{}", record);
+/// }
+/// ```
+pub struct Resolver {
+    config: ResolverConfig,
+    transport: Box<dyn Transport>,
+    cache: RefCell<Cache>,
+    servers: RefCell<ServerTable>,
+}
+impl Resolver {
+    /// Opens the tap device of the config and resolves the MAC address of the gateway. Fails
+    /// when `exploration` is outside 0.0 to 1.0.
+    pub fn new(config: ResolverConfig) -> Result<Self, String> {
+        if !(0.0..=1.0).contains(&config.exploration) {
+            return Err(format!(
+                "exploration out of 0.0 to 1.0: {}",
+                config.exploration
+            ));
+        }
+        let transport = TapTransport::new(&config)?;
+        return Ok(Resolver::with_transport(config, Box::new(transport)));
+    }
+
+    /// An `exploration` outside 0.0 to 1.0 is clamped to that range.
+    pub fn with_transport(mut config: ResolverConfig, transport: Box<dyn Transport>) -> Self {
+        config.exploration = if config.exploration.is_nan() {
+            0.0
+        } else {
+            config.exploration.clamp(0.0, 1.0)
+        };
+        return Resolver {
+            config,
+            transport,
+            cache: RefCell::new(Cache::new()),
+            servers: RefCell::new(ServerTable::new()),
+        };
+    }
+
+    pub fn config(&self) -> &ResolverConfig {
+        return &self.config;
+    }
+
+    /// The round trip times and failures of the servers asked so far.
+    pub fn servers(&self) -> ServerTable {
+        return self.servers.borrow().clone();
+    }
+
+    /// Fails when the config's limits on nesting, CNAME chains, referrals or queries are hit, or
+    /// when resolving would go round in a loop.
+    pub fn resolve(&self, name: &DomainName, q_type: DnsType) -> Result<Answer, ResolveError> {
+        let mut resolution = Resolution::new();
+        let depth = Depth::default();
+        let root_trust = if self.config.dnssec {
+            // nothing is trusted until the root keys are, they are fetched once per TTL
+            let root = self.root_delegation(&Err(ValidationStatus::Indeterminate));
+            self.fetch_zone_keys(&root, &self.config.trust_anchors, depth, &mut resolution)?
+        } else {
+            // nothing to anchor the chain of trust to
+            Err(ValidationStatus::Indeterminate)
+        };
+
+        return self.resolve_with(name, q_type, None, &root_trust, depth, &mut resolution);
+    }
+
+    fn log(&self, line: &str) {
+        (self.config.log)(line);
+    }
+
+    fn now(&self) -> u32 {
+        return (self.config.clock)();
+    }
+
+    fn root_delegation(&self, root_trust: &Trust) -> Delegation {
+        return Delegation {
+            zone: DomainName::root(),
+            name_servers: Vec::new(),
+            addresses: self.config.root_hints.clone(),
+            trust: root_trust.clone(),
+        };
+    }
+
+    fn exchange(
+        &self,
+        query: &DnsMessage,
+        server_ipaddr: &str,
+        exact_case: bool,
+        log_label: &str,
+        depth: Depth,
+    ) -> Result<DnsMessage, String> {
+        self.log(&format!(
+            "{} send dns request to {}...",
+            log_label, server_ipaddr
+        ));
+        let message = self.transport.exchange(query, server_ipaddr, exact_case)?;
+
+        self.log(&format!("{} dns reply received:", log_label));
+        match print_message(&message, self.config.format) {
+            Ok(json) if self.config.format == MessageFormat::Json => (self.config.json_log)(&json),
+            Ok(text) => self.log(
+                &format!("|   {}", text)
+                    .replace("\n", "\n|   ")
+                    .replace("|   ", &(depth.indent() + "|   ")),
+            ),
+            Err(error) => self.log(&format!(
+                "{} dns reply not printed. error={}",
+                log_label, error
+            )),
+        }
+
+        return Ok(message);
+    }
+
+    // sends the query to one server until it replies or the retries run out. A reply with an
+    // error other than NXDOMAIN counts as a failure of the server.
+    fn query_server(
+        &self,
+        query: &QueryBuilder,
+        server_ipaddr: &str,
+        log_label: &str,
+        depth: Depth,
+        resolution: &mut Resolution,
+    ) -> Result<DnsMessage, ResolveError> {
+        // the server would only give the same reply again
+        let question = &query.questions()[0];
+        let asked = (
+            question.q_name.clone(),
+            question.q_type,
+            server_ipaddr.to_string(),
+        );
+        if !resolution.asked.insert(asked) {
+            return Err(ResolveError::LoopDetected(format!(
+                "{} asked about {} {} again",
+                server_ipaddr, question.q_name, question.q_type
+            )));
+        }
+
+        let mut error = String::new();
+        let mut attempt = 0;
+        while attempt <= self.config.retries {
+            if resolution.queries >= self.config.max_queries {
+                return Err(ResolveError::QueryBudgetExhausted(resolution.queries));
+            }
+            resolution.queries += 1;
+            if attempt > 0 {
+                self.log(&format!(
+                    "{} retrying {}... attempt={} error={}",
+                    log_label,
+                    server_ipaddr,
+                    attempt + 1,
+                    error
+                ));
+            }
+
+            // servers that do not echo the case back are asked without it
+            let randomize_case = self.config.case_randomization
+                && !self.servers.borrow().mangles_case(server_ipaddr);
+            // the id is one of the things a forged reply has to guess, so every query gets its
+            // own. The thread rng is a CSPRNG
+            let query = query
+                .clone()
+                .id(random())
+                .randomize_case(randomize_case)
+                .build();
+
+            let sent_at = Instant::now();
+            let message =
+                match self.exchange(&query, server_ipaddr, randomize_case, log_label, depth) {
+                    Ok(message) => message,
+                    Err(exchange_error) => {
+                        self.servers
+                            .borrow_mut()
+                            .record_failure(server_ipaddr, self.config.timeout);
+                        error = exchange_error;
+                        attempt += 1;
+                        continue;
+                    }
+                };
+
+            // a reply that does not echo the case is only returned when no other came in time. It
+            // may be forged, so it is not used and only a few in a row mark the server
+            if randomize_case {
+                if !echoes_case(&message, &query) {
+                    self.servers
+                        .borrow_mut()
+                        .record_case_mismatch(server_ipaddr);
+                    error = "the case of the question was not echoed".to_string();
+                    attempt += 1;
+                    continue;
+                }
+                self.servers.borrow_mut().record_case_echoed(server_ipaddr);
+            }
+
+            return match message.rcode() {
+                DnsHeaderRcode::NoError | DnsHeaderRcode::NoNameError => {
+                    self.servers
+                        .borrow_mut()
+                        .record_rtt(server_ipaddr, sent_at.elapsed());
+                    Ok(message)
+                }
+                rcode => {
+                    self.servers
+                        .borrow_mut()
+                        .record_failure(server_ipaddr, self.config.timeout);
+                    Err(ResolveError::ServerFailure(format!(
+                        "{} replied {}",
+                        server_ipaddr, rcode
+                    )))
+                }
+            };
+        }
+
+        return Err(ResolveError::ServerFailure(format!(
+            "{}: {}",
+            server_ipaddr, error
+        )));
+    }
+
+    // asks the servers of the delegation in turn, the fastest first. The names of servers without
+    // glue are only resolved once every known address failed. Only failures of the servers are
+    // tried past, hitting a limit ends the resolution.
+    fn query_delegation(
+        &self,
+        query: &QueryBuilder,
+        delegation: &Delegation,
+        root_trust: &Trust,
+        log_label: &str,
+        depth: Depth,
+        resolution: &mut Resolution,
+    ) -> Result<DnsMessage, ResolveError> {
+        let mut errors = Vec::new();
+        let try_servers = |addresses: &[String],
+                           errors: &mut Vec<String>,
+                           resolution: &mut Resolution|
+         -> Result<Option<DnsMessage>, ResolveError> {
+            let addresses = self
+                .servers
+                .borrow()
+                .order(addresses, self.config.exploration);
+            for address in &addresses {
+                match self.query_server(query, address, log_label, depth, resolution) {
+                    Ok(message) => return Ok(Some(message)),
+                    Err(ResolveError::ServerFailure(error)) => {
+                        self.log(&format!(
+                            "{} server failed, trying the next one... error={}",
+                            log_label, error
+                        ));
+                        errors.push(error);
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+            return Ok(None);
+        };
+
+        if let Some(message) = try_servers(&delegation.addresses, &mut errors, resolution)? {
+            return Ok(message);
+        }
+        let mut tried = delegation.addresses.clone();
+
+        for name_server_name in &delegation.name_servers {
+            // as when two zones are served only by name servers in each other
+            if resolution.name_server_lookups.contains(name_server_name) {
+                return Err(ResolveError::LoopDetected(format!(
+                    "the address of {} is needed to resolve itself",
+                    name_server_name
+                )));
+            }
+
+            self.log(&format!(
+                "{} resolving name server address... server_name={}",
+                log_label, name_server_name
+            ));
+            resolution
+                .name_server_lookups
+                .push(name_server_name.clone());
+            let name_server_answer = self.resolve_with(
+                name_server_name,
+                DnsType::A,
+                None,
+                root_trust,
+                depth.lookup(),
+                resolution,
+            );
+            resolution.name_server_lookups.pop();
+
+            let addresses: Vec<String> = match name_server_answer {
+                Ok(answer) => answer
+                    .records
+                    .iter()
+                    .filter_map(|rr| match &rr.rdata {
+                        RData::A(address) => Some(address.to_string()),
+                        _ => None,
+                    })
+                    // asking an address again would be taken for a loop
+                    .filter(|address| !tried.contains(address))
+                    .collect(),
+                Err(error @ ResolveError::ServerFailure(_)) => {
+                    errors.push(format!("{}: {}", name_server_name, error));
+                    continue;
+                }
+                Err(error) => return Err(error),
+            };
+            if addresses.is_empty() {
+                errors.push(format!("{}: no address not tried yet", name_server_name));
+                continue;
+            }
+            tried.extend(addresses.iter().cloned());
+
+            if let Some(message) = try_servers(&addresses, &mut errors, resolution)? {
+                return Ok(message);
+            }
+        }
+
+        let msg = format!(
+            "every server of {} failed: {}",
+            delegation.zone,
+            errors.join(", ")
+        );
+        self.log(&format!("{} {}", log_label, msg));
+        return Err(ResolveError::ServerFailure(msg));
+    }
+
+    // asks the servers of the zone for its DNSKEY RRset and validates it with the DS RRset
+    fn fetch_zone_keys(
+        &self,
+        delegation: &Delegation,
+        ds_set: &[RData],
+        depth: Depth,
+        resolution: &mut Resolution,
+    ) -> Result<Trust, ResolveError> {
+        let zone = &delegation.zone;
+        let cached_keys = self.cache.borrow_mut().get_zone_keys(zone, self.now());
+        if let Some(zone_keys) = cached_keys {
+            self.log(&format!(
+                "{}[{} DNSKEY in cache] validated DNSKEY RRset found. keys={}",
+                depth.indent(),
+                zone,
+                zone_keys.dnskeys.len()
+            ));
+            return Ok(Ok(zone_keys));
+        }
+
+        let log_label = format!("{}[{} DNSKEY in {}]", depth.indent(), zone, zone);
+
+        let edns = Edns {
+            udp_payload_size: 1232,
+            extended_rcode: 0,
+            version: 0,
+            dnssec_ok: true,
+            options: Vec::new(),
+        };
+        let query = QueryBuilder::new(0)
+            .question(zone, DnsType::DNSKEY, DnsClass::IN)
+            .edns(edns);
+        // the name servers of the zone are looked up without validation
+        let message = self.query_delegation(
+            &query,
+            delegation,
+            &Err(ValidationStatus::Indeterminate),
+            &log_label,
+            depth,
+            resolution,
+        )?;
+
+        let zone_keys = validate_dnskeys(zone, &message, ds_set, self.now());
+        match &zone_keys {
+            Ok(zone_keys) => {
+                self.log(&format!(
+                    "{} DNSKEY RRset validated. keys={}",
+                    log_label,
+                    zone_keys.dnskeys.len()
+                ));
+                // kept as long as the validated RRset
+                let ttl = message
+                    .answers
+                    .iter()
+                    .filter(|rr| rr.rr_type == DnsType::DNSKEY && &rr.name == zone)
+                    .map(|rr| rr.ttl)
+                    .min()
+                    .unwrap_or(0);
+                self.cache
+                    .borrow_mut()
+                    .insert_zone_keys(zone_keys.clone(), ttl, self.now());
+            }
+            Err(status) => self.log(&format!(
+                "{} DNSKEY RRset not validated. status={}",
+                log_label, status
+            )),
+        }
+
+        return Ok(zone_keys);
+    }
+
+    // the keys of a zone below the delegation's that its servers serve as well. The DS RRset of
+    // the zone is asked from the same servers and validated with the keys of the zone above,
+    // which are found the same way when that one is below the delegation's too.
+    fn fetch_signer_keys(
+        &self,
+        delegation: &Delegation,
+        signer: &DomainName,
+        depth: Depth,
+        resolution: &mut Resolution,
+    ) -> Result<Trust, ResolveError> {
+        if delegation.trust.is_err() {
+            return Ok(delegation.trust.clone());
+        }
+        let cached_keys = self.cache.borrow_mut().get_zone_keys(signer, self.now());
+        if let Some(zone_keys) = cached_keys {
+            self.log(&format!(
+                "{}[{} DNSKEY in cache] validated DNSKEY RRset found. keys={}",
+                depth.indent(),
+                signer,
+                zone_keys.dnskeys.len()
+            ));
+            return Ok(Ok(zone_keys));
+        }
+
+        let log_label = format!("{}[{} DS in {}]", depth.indent(), signer, delegation.zone);
+
+        let edns = Edns {
+            udp_payload_size: 1232,
+            extended_rcode: 0,
+            version: 0,
+            dnssec_ok: true,
+            options: Vec::new(),
+        };
+        let query = QueryBuilder::new(0)
+            .question(signer, DnsType::DS, DnsClass::IN)
+            .edns(edns);
+        let message = self.query_delegation(
+            &query,
+            delegation,
+            &Err(ValidationStatus::Indeterminate),
+            &log_label,
+            depth,
+            resolution,
+        )?;
+
+        let parent_trust = match find_descendant_signer(&message, &delegation.zone, signer) {
+            Some(parent) if &parent != signer => {
+                self.fetch_signer_keys(delegation, &parent, depth.lookup(), resolution)?
+            }
+            _ => delegation.trust.clone(),
+        };
+        let status = match &parent_trust {
+            Ok(zone_keys) => {
+                validate_response(zone_keys, &message, signer, DnsType::DS, self.now())
+            }
+            Err(status) => *status,
+        };
+        let ds_set: Vec<RData> = message
+            .answers
+            .iter()
+            .filter(|rr| rr.rr_type == DnsType::DS && &rr.name == signer)
+            .map(|rr| rr.rdata.clone())
+            .collect();
+        if status != ValidationStatus::Secure {
+            self.log(&format!(
+                "{} DS RRset not validated. status={}",
+                log_label, status
+            ));
+            return Ok(Err(status));
+        }
+        if ds_set.is_empty() {
+            self.log(&format!("{} no DS RRset, the zone is unsigned.", log_label));
+            return Ok(Err(ValidationStatus::Insecure));
+        }
+        self.log(&format!(
+            "{} DS RRset validated. records={}",
+            log_label,
+            ds_set.len()
+        ));
+
+        let signer_delegation = Delegation {
+            zone: signer.clone(),
+            name_servers: delegation.name_servers.clone(),
+            addresses: delegation.addresses.clone(),
+            trust: Err(ValidationStatus::Indeterminate),
+        };
+        return self.fetch_zone_keys(&signer_delegation, &ds_set, depth.lookup(), resolution);
+    }
+
+    // answers from the cache when it can. Otherwise asks the servers of the given delegation,
+    // the closest cached one or the root, and follows referrals and CNAMEs.
+    fn resolve_with(
+        &self,
+        name: &DomainName,
+        q_type: DnsType,
+        delegation: Option<Delegation>,
+        root_trust: &Trust,
+        depth: Depth,
+        resolution: &mut Resolution,
+    ) -> Result<Answer, ResolveError> {
+        if depth.nesting > self.config.max_depth {
+            return Err(ResolveError::MaxDepthExceeded(depth.nesting));
+        }
+        // a CNAME loop ends here too, the CNAMEs come from the cache once they are looked up
+        if depth.cnames > self.config.max_cname_chain {
+            return Err(ResolveError::CnameChainTooLong(depth.cnames));
+        }
+        if depth.referrals > self.config.max_referrals {
+            return Err(ResolveError::TooManyReferrals(depth.referrals));
+        }
+
+        let cache_label = format!("{}[{} {} in cache]", depth.indent(), name, q_type);
+        let cached_rrset = self.cache.borrow_mut().get_rrset(name, q_type, self.now());
+        if let Some((records, status)) = cached_rrset {
+            self.log(&format!(
+                "{} target {} resource record found. status={}",
+                cache_label, q_type, status
+            ));
+            return Ok(Answer {
+                name: name.clone(),
+                q_type,
+                rcode: DnsHeaderRcode::NoError,
+                records,
+                status,
+            });
+        }
+
+        let cached_negative = self
+            .cache
+            .borrow_mut()
+            .get_negative(name, q_type, self.now());
+        if let Some((rcode, status)) = cached_negative {
+            self.log(&format!(
+                "{} no {} resource record. rcode={} status={}",
+                cache_label, q_type, rcode, status
+            ));
+            return Ok(Answer {
+                name: name.clone(),
+                q_type,
+                rcode,
+                records: Vec::new(),
+                status,
+            });
+        }
+
+        let cached_cname = match q_type {
+            DnsType::CNAME => None,
+            _ => self
+                .cache
+                .borrow_mut()
+                .get_rrset(name, DnsType::CNAME, self.now()),
+        };
+        if let Some((cname_rrset, status)) = cached_cname {
+            let RData::CNAME(cname) = &cname_rrset[0].rdata else {
+                unreachable!();
+            };
+            self.log(&format!(
+                "{} canonical name found, resolving... cname={} status={}",
+                cache_label, cname, status
+            ));
+
+            let cname_answer =
+                self.resolve_with(cname, q_type, None, root_trust, depth.cname(), resolution)?;
+
+            return Ok(Answer {
+                name: name.clone(),
+                q_type,
+                rcode: cname_answer.rcode,
+                records: [cname_rrset, cname_answer.records].concat(),
+                status: status.combine(cname_answer.status),
+            });
+        }
+
+        let delegation = match delegation {
+            Some(delegation) => delegation,
+            None => {
+                let cached_delegation = self.cache.borrow_mut().get_delegation(name, self.now());
+                match cached_delegation {
+                    Some(delegation) => {
+                        self.log(&format!(
+                            "{} delegation found. zone={}",
+                            cache_label, delegation.zone
+                        ));
+                        delegation
+                    }
+                    None => self.root_delegation(root_trust),
+                }
+            }
+        };
+        let trust = &delegation.trust;
+        let log_label = format!(
+            "{}[{} {} in {}]",
+            depth.indent(),
+            name,
+            q_type,
+            delegation.zone
+        );
+
+        // advertise a larger buffer so that referrals with many glue records fit in a frame.
+        // see: https://www.dnsflagday.net/2020/
+        let edns = Edns {
+            udp_payload_size: 1232,
+            extended_rcode: 0,
+            version: 0,
+            dnssec_ok: trust.is_ok(),
+            options: Vec::new(),
+        };
+        let query = QueryBuilder::new(0)
+            .question(name, q_type, DnsClass::IN)
+            .edns(edns);
+
+        let mut message = self.query_delegation(
+            &query,
+            &delegation,
+            root_trust,
+            &log_label,
+            depth,
+            resolution,
+        )?;
+        self.discard_out_of_zone(&mut message, &delegation.zone, &log_label);
+
+        // the servers may serve a zone below the delegated one too, which signs its own records
+        let trust = &match find_descendant_signer(&message, &delegation.zone, name) {
+            Some(signer) if trust.is_ok() => {
+                self.log(&format!(
+                    "{} signed by a zone below, fetching its keys... signer={}",
+                    log_label, signer
+                ));
+                self.fetch_signer_keys(&delegation, &signer, depth.lookup(), resolution)?
+            }
+            _ => trust.clone(),
+        };
+
+        // only answers and denials from the zone can be validated, not referrals
+        let validate = || match trust {
+            Ok(zone_keys) => validate_response(zone_keys, &message, name, q_type, self.now()),
+            Err(status) => *status,
+        };
+
+        if message.rcode() == DnsHeaderRcode::NoNameError {
+            let status = validate();
+            self.log(&format!(
+                "{} name does not exist. status={}",
+                log_label, status
+            ));
+            self.cache_negative(&message, name, q_type, status, &log_label);
+            return Ok(Answer {
+                name: name.clone(),
+                q_type,
+                rcode: DnsHeaderRcode::NoNameError,
+                records: Vec::new(),
+                status,
+            });
+        }
+
+        let find_rrset = |records: &Vec<DnsResourceRecord>, owner: &DomainName| {
+            return records
+                .iter()
+                .filter(|rr| {
+                    rr.rr_class == DnsClass::IN && rr.rr_type == q_type && &rr.name == owner
+                })
+                .cloned()
+                .collect::<Vec<DnsResourceRecord>>();
+        };
+
+        let answer_rrset = find_rrset(&message.answers, name);
+        if !answer_rrset.is_empty() {
+            let status = validate();
+            self.log(&format!(
+                "{} target {} resource record found in answer section. status={}",
+                log_label, q_type, status
+            ));
+            self.cache
+                .borrow_mut()
+                .insert_rrset(&answer_rrset, status, self.now());
+            return Ok(Answer {
+                name: name.clone(),
+                q_type,
+                rcode: DnsHeaderRcode::NoError,
+                records: answer_rrset,
+                status,
+            });
+        }
+
+        let additional_rrset = find_rrset(&message.additionals, name);
+        if !additional_rrset.is_empty() {
+            // records in the additional section are not validated
+            let status = trust
+                .as_ref()
+                .err()
+                .copied()
+                .unwrap_or(ValidationStatus::Indeterminate);
+            self.log(&format!(
+                "{} target {} resource record found in additional section. status={}",
+                log_label, q_type, status
+            ));
+            return Ok(Answer {
+                name: name.clone(),
+                q_type,
+                rcode: DnsHeaderRcode::NoError,
+                records: additional_rrset,
+                status,
+            });
+        }
+
+        let cname_rr = message.answers.iter().find(|rr| {
+            q_type != DnsType::CNAME
+                && rr.rr_class == DnsClass::IN
+                && &rr.name == name
+                && matches!(rr.rdata, RData::CNAME(_))
+        });
+        if let Some(cname_rr) = cname_rr {
+            let RData::CNAME(cname) = &cname_rr.rdata else {
+                unreachable!();
+            };
+            let status = validate();
+            self.log(&format!(
+                "{} canonical name found. cname={} status={}",
+                log_label, cname, status
+            ));
+            self.cache
+                .borrow_mut()
+                .insert_rrset(slice::from_ref(cname_rr), status, self.now());
+
+            let rrset_for_cname = find_rrset(&message.answers, cname);
+            if !rrset_for_cname.is_empty() {
+                self.log(&format!(
+                    "{} {} resource record for canonical name found.",
+                    log_label, q_type
+                ));
+                self.cache
+                    .borrow_mut()
+                    .insert_rrset(&rrset_for_cname, status, self.now());
+                return Ok(Answer {
+                    name: name.clone(),
+                    q_type,
+                    rcode: DnsHeaderRcode::NoError,
+                    records: [vec![cname_rr.clone()], rrset_for_cname].concat(),
+                    status,
+                });
+            }
+
+            self.log(&format!(
+                "{} {} resource record for canonical name not found, resolving... cname={}",
+                log_label, q_type, cname
+            ));
+
+            let cname_answer =
+                self.resolve_with(cname, q_type, None, root_trust, depth.cname(), resolution)?;
+
+            self.log(&format!(
+                "{} canonical name resolved. name={} cname={}",
+                log_label, name, cname
+            ));
+
+            return Ok(Answer {
+                name: name.clone(),
+                q_type,
+                rcode: cname_answer.rcode,
+                records: [vec![cname_rr.clone()], cname_answer.records].concat(),
+                status: status.combine(cname_answer.status),
+            });
+        }
+
+        // a referral is only followed to a zone the name is in
+        let child_zones: Vec<&DomainName> = message
+            .authorities
+            .iter()
+            .filter(|rr| rr.rr_class == DnsClass::IN && matches!(rr.rdata, RData::NS(_)))
+            .filter(|rr| {
+                if name.is_subdomain_of(&rr.name) {
+                    return true;
+                }
+                self.log(&format!(
+                    "{} NS resource record discarded, {} is not in the zone. zone={}",
+                    log_label, name, rr.name
+                ));
+                return false;
+            })
+            .map(|rr| &rr.name)
+            .collect();
+        let child_zone = child_zones.first().copied();
+        // a denial from the zone has its SOA in the authority section, perhaps with the NS RRset.
+        // see: https://www.rfc-editor.org/rfc/rfc2308#section-2.2
+        let has_soa = message
+            .authorities
+            .iter()
+            .any(|rr| matches!(rr.rdata, RData::SOA { .. }));
+        if let Some(child_zone) = child_zone
+            && !has_soa
+        {
+            let (mut child_delegation, ttl) =
+                self.create_delegation(&message, child_zone, &log_label);
+            self.log(&format!(
+                "{} name server resource record found. zone={} addresses={} without_glue={}",
+                log_label,
+                child_zone,
+                child_delegation.addresses.len(),
+                child_delegation.name_servers.len()
+            ));
+
+            // the chain of trust goes on only through a signed DS RRset
+            child_delegation.trust = match trust {
+                Ok(zone_keys) => {
+                    match validate_referral(zone_keys, &message, child_zone, self.now()) {
+                        Ok(ds_set) => self.fetch_zone_keys(
+                            &child_delegation,
+                            &ds_set,
+                            depth.lookup(),
+                            resolution,
+                        )?,
+                        Err(status) => Err(status),
+                    }
+                }
+                Err(status) => Err(*status),
+            };
+            self.cache
+                .borrow_mut()
+                .insert_delegation(child_delegation.clone(), ttl, self.now());
+
+            self.log(&format!(
+                "{} resolving with the name servers of {}...",
+                log_label, child_zone
+            ));
+
+            let answer = self.resolve_with(
+                name,
+                q_type,
+                Some(child_delegation),
+                root_trust,
+                depth.referral(),
+                resolution,
+            )?;
+
+            self.log(&format!(
+                "{} resolving with another name server done. rcode={} records={}",
+                log_label,
+                answer.rcode,
+                answer.records.len(),
+            ));
+
+            return Ok(answer);
+        }
+
+        let status = validate();
+        self.log(&format!(
+            "{} no {} resource record for the name. status={}",
+            log_label, q_type, status
+        ));
+        self.cache_negative(&message, name, q_type, status, &log_label);
+        return Ok(Answer {
+            name: name.clone(),
+            q_type,
+            rcode: DnsHeaderRcode::NoError,
+            records: Vec::new(),
+            status,
+        });
+    }
+
+    // drops the records a server has no authority for, all of those outside its zone.
+    // see: https://www.rfc-editor.org/rfc/rfc2181#section-5.4.1
+    fn discard_out_of_zone(&self, message: &mut DnsMessage, zone: &DomainName, log_label: &str) {
+        for (section, records) in [
+            ("answer", &mut message.answers),
+            ("authority", &mut message.authorities),
+            ("additional", &mut message.additionals),
+        ] {
+            records.retain(|rr| {
+                if rr.name.is_subdomain_of(zone) {
+                    return true;
+                }
+                self.log(&format!(
+                    "{} {} resource record in {} section discarded, out of zone. name={} zone={}",
+                    log_label, rr.rr_type, section, rr.name, zone
+                ));
+                return false;
+            });
+        }
+    }
+
+    // the name servers of the zone from a referral, with the glue given for them. Glue is only
+    // taken for names in the zone. The delegation is kept as long as the NS RRset and the glue,
+    // its trust is up to the caller.
+    fn create_delegation(
+        &self,
+        message: &DnsMessage,
+        zone: &DomainName,
+        log_label: &str,
+    ) -> (Delegation, u32) {
+        let ns_rrset: Vec<&DnsResourceRecord> = message
+            .authorities
+            .iter()
+            .filter(|rr| {
+                rr.rr_class == DnsClass::IN && &rr.name == zone && matches!(rr.rdata, RData::NS(_))
+            })
+            .collect();
+        let name_servers: Vec<DomainName> = ns_rrset
+            .iter()
+            .filter_map(|rr| match &rr.rdata {
+                RData::NS(server_name) => Some(server_name.clone()),
+                _ => None,
+            })
+            .collect();
+        let glue: Vec<&DnsResourceRecord> = message
+            .additionals
+            .iter()
+            .filter(|rr| {
+                rr.rr_class == DnsClass::IN
+                    && matches!(rr.rdata, RData::A(_))
+                    && name_servers.contains(&rr.name)
+            })
+            .filter(|rr| {
+                if rr.name.is_subdomain_of(zone) {
+                    return true;
+                }
+                self.log(&format!(
+                    "{} glue discarded, out of the delegated zone. name={} zone={}",
+                    log_label, rr.name, zone
+                ));
+                return false;
+            })
+            .collect();
+
+        let addresses: Vec<String> = glue.iter().map(|rr| rr.rdata.to_string()).collect();
+        let name_servers_without_glue: Vec<DomainName> = name_servers
+            .into_iter()
+            .filter(|server_name| !glue.iter().any(|rr| &rr.name == server_name))
+            .collect();
+        let ttl = ns_rrset
+            .iter()
+            .chain(glue.iter())
+            .map(|rr| rr.ttl)
+            .min()
+            .unwrap_or(0);
+
+        return (
+            Delegation {
+                zone: zone.clone(),
+                name_servers: name_servers_without_glue,
+                addresses,
+                trust: Err(ValidationStatus::Indeterminate),
+            },
+            ttl,
+        );
+    }
+
+    // keeps a denial as long as both the SOA TTL and the SOA MINIMUM allow, and not at all
+    // without an SOA. see: https://www.rfc-editor.org/rfc/rfc2308#section-5
+    fn cache_negative(
+        &self,
+        message: &DnsMessage,
+        name: &DomainName,
+        q_type: DnsType,
+        status: ValidationStatus,
+        log_label: &str,
+    ) {
+        let ttl = message.authorities.iter().find_map(|rr| match &rr.rdata {
+            RData::SOA { minimum, .. } if rr.rr_class == DnsClass::IN => Some(rr.ttl.min(*minimum)),
+            _ => None,
+        });
+        let Some(ttl) = ttl else {
+            self.log(&format!("{} no SOA, not cached.", log_label));
+            return;
+        };
+
+        self.cache.borrow_mut().insert_negative(
+            name,
+            q_type,
+            message.rcode(),
+            status,
+            ttl,
+            self.now(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod resolver {
+    use std::{
+        cell::RefCell,
+        collections::HashSet,
+        net::Ipv4Addr,
+        rc::Rc,
+        sync::{
+            Arc, Mutex,
+            atomic::{AtomicU32, Ordering},
+        },
+        time::Duration,
+    };
+
+    use crate::{
+        dns::{
+            DnsClass, DnsHeaderRcode, DnsQuestionSectionEntry, DnsResourceRecord, DnsType,
+            DomainName, RData, ValidationStatus,
+            fixtures::{Zone, create_message, create_rr},
+        },
+        resolver::{
+            config::ResolverConfig,
+            error::ResolveError,
+            fixtures::{ScriptedTransport, scripted_reply},
+            iterative::Resolver,
+        },
+    };
+
+    type Queries = Rc<RefCell<Vec<(String, DnsQuestionSectionEntry)>>>;
+
+    fn create_resolver(
+        replies: Vec<(&str, &str, DnsHeaderRcode, &str, &str, &str)>,
+        max_depth: u16,
+    ) -> (Resolver, Queries, Arc<AtomicU32>) {
+        return create_resolver_with(replies, |config, _| config.max_depth = max_depth);
+    }
+
+    // replies as (server, question, rcode, answers, authorities, additionals). The clock reads
+    // the returned time.
+    fn create_resolver_with(
+        replies: Vec<(&str, &str, DnsHeaderRcode, &str, &str, &str)>,
+        configure: impl FnOnce(&mut ResolverConfig, &mut ScriptedTransport),
+    ) -> (Resolver, Queries, Arc<AtomicU32>) {
+        let mut transport = ScriptedTransport {
+            replies: replies
+                .into_iter()
+                .map(
+                    |(server, question, rcode, answers, authorities, additionals)| {
+                        let (question, reply) =
+                            scripted_reply(question, rcode, answers, authorities, additionals);
+                        (server.to_string(), question, reply)
+                    },
+                )
+                .collect(),
+            queries: Rc::new(RefCell::new(Vec::new())),
+            ids: Rc::new(RefCell::new(Vec::new())),
+            case_mangling_servers: Vec::new(),
+        };
+        let queries = transport.queries.clone();
+        let time = Arc::new(AtomicU32::new(1_700_000_000));
+        let clock_time = time.clone();
+        let mut config = ResolverConfig {
+            root_hints: vec!["198.41.0.4".to_string()],
+            exploration: 0.0,
+            log: Arc::new(|_| {}),
+            clock: Arc::new(move || clock_time.load(Ordering::Relaxed)),
+            ..Default::default()
+        };
+        configure(&mut config, &mut transport);
+
+        return (
+            Resolver::with_transport(config, Box::new(transport)),
+            queries,
+            time,
+        );
+    }
+
+    fn print_queries(queries: &Queries) -> Vec<String> {
+        return queries
+            .borrow()
+            .iter()
+            .map(|(server, question)| format!("{} {} {}", server, question.q_name, question.q_type))
+            .collect();
+    }
+
+    #[test]
+    fn case1() {
+        // referrals from the root down to the answer, with and without glue
+        let (resolver, queries, _) = create_resolver(
+            vec![
+                (
+                    "198.41.0.4",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "com. 172800 NS a.gtld-servers.com.",
+                    "a.gtld-servers.com. 172800 A 192.5.6.30",
+                ),
+                (
+                    "192.5.6.30",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "example.com. 172800 NS ns.example.net.",
+                    "",
+                ),
+                (
+                    "198.41.0.4",
+                    "ns.example.net. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "net. 172800 NS a.gtld-servers.net.",
+                    "a.gtld-servers.net. 172800 A 192.5.6.30",
+                ),
+                (
+                    "192.5.6.30",
+                    "ns.example.net. A",
+                    DnsHeaderRcode::NoError,
+                    "ns.example.net. 3600 A 192.0.2.53",
+                    "",
+                    "",
+                ),
+                (
+                    "192.0.2.53",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "www.example.com. 300 A 192.0.2.80",
+                    "",
+                    "",
+                ),
+            ],
+            16,
+        );
+
+        let answer = resolver
+            .resolve(&"www.example.com.".parse().unwrap(), DnsType::A)
+            .unwrap();
+        assert_eq!(answer.rcode, DnsHeaderRcode::NoError);
+        assert_eq!(answer.status, ValidationStatus::Indeterminate);
+        assert_eq!(
+            answer
+                .records
+                .iter()
+                .map(|rr| rr.to_string())
+                .collect::<Vec<String>>(),
+            vec!["www.example.com.\t300\tIN\tA\t192.0.2.80"]
+        );
+        assert_eq!(
+            print_queries(&queries),
+            vec![
+                "198.41.0.4 www.example.com. A",
+                "192.5.6.30 www.example.com. A",
+                "198.41.0.4 ns.example.net. A",
+                "192.5.6.30 ns.example.net. A",
+                "192.0.2.53 www.example.com. A",
+            ]
+        );
+    }
+
+    #[test]
+    fn case2() {
+        // a CNAME whose target is not in the answer is resolved from the root
+        let (resolver, queries, _) = create_resolver(
+            vec![
+                (
+                    "198.41.0.4",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "www.example.com. 300 CNAME cdn.example.net.",
+                    "",
+                    "",
+                ),
+                (
+                    "198.41.0.4",
+                    "cdn.example.net. A",
+                    DnsHeaderRcode::NoError,
+                    "cdn.example.net. 60 A 192.0.2.80",
+                    "",
+                    "",
+                ),
+            ],
+            16,
+        );
+
+        let answer = resolver
+            .resolve(&"www.example.com.".parse().unwrap(), DnsType::A)
+            .unwrap();
+        assert_eq!(
+            answer
+                .records
+                .iter()
+                .map(|rr| rr.to_string())
+                .collect::<Vec<String>>(),
+            vec![
+                "www.example.com.\t300\tIN\tCNAME\tcdn.example.net.",
+                "cdn.example.net.\t60\tIN\tA\t192.0.2.80",
+            ]
+        );
+        assert_eq!(
+            print_queries(&queries),
+            vec![
+                "198.41.0.4 www.example.com. A",
+                "198.41.0.4 cdn.example.net. A"
+            ]
+        );
+    }
+
+    #[test]
+    fn case3() {
+        // NXDOMAIN is an answer, other errors are not
+        let (resolver, _, _) = create_resolver(
+            vec![
+                (
+                    "198.41.0.4",
+                    "nothing.example. A",
+                    DnsHeaderRcode::NoNameError,
+                    "",
+                    "",
+                    "",
+                ),
+                (
+                    "198.41.0.4",
+                    "refused.example. A",
+                    DnsHeaderRcode::Refused,
+                    "",
+                    "",
+                    "",
+                ),
+            ],
+            16,
+        );
+
+        let answer = resolver
+            .resolve(&"nothing.example.".parse().unwrap(), DnsType::A)
+            .unwrap();
+        assert_eq!(answer.rcode, DnsHeaderRcode::NoNameError);
+        assert_eq!(answer.records, Vec::new());
+
+        let error = resolver
+            .resolve(&"refused.example.".parse().unwrap(), DnsType::A)
+            .unwrap_err();
+        assert!(matches!(error, ResolveError::ServerFailure(_)));
+    }
+
+    #[test]
+    fn case4() {
+        // referrals deeper than max_depth
+        let (resolver, _, _) = create_resolver(
+            vec![
+                (
+                    "198.41.0.4",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "com. 172800 NS a.gtld-servers.com.",
+                    "a.gtld-servers.com. 172800 A 192.5.6.30",
+                ),
+                (
+                    "192.5.6.30",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "example.com. 172800 NS ns.example.com.",
+                    "ns.example.com. 172800 A 192.0.2.53",
+                ),
+            ],
+            1,
+        );
+
+        let error = resolver
+            .resolve(&"www.example.com.".parse().unwrap(), DnsType::A)
+            .unwrap_err();
+        assert_eq!(error, ResolveError::MaxDepthExceeded(2));
+    }
+
+    #[test]
+    fn case5() {
+        // cached answers and delegations until their TTLs run out
+        let (resolver, queries, time) = create_resolver(
+            vec![
+                (
+                    "198.41.0.4",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "com. 172800 NS a.gtld-servers.com.",
+                    "a.gtld-servers.com. 172800 A 192.5.6.30",
+                ),
+                (
+                    "192.5.6.30",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "example.com. 3600 NS ns.example.com.",
+                    "ns.example.com. 3600 A 192.0.2.53",
+                ),
+                (
+                    "192.0.2.53",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "www.example.com. 300 A 192.0.2.80",
+                    "",
+                    "",
+                ),
+                (
+                    "192.0.2.53",
+                    "mail.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "mail.example.com. 300 A 192.0.2.25",
+                    "",
+                    "",
+                ),
+                (
+                    "192.5.6.30",
+                    "mail.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "example.com. 3600 NS ns.example.com.",
+                    "ns.example.com. 3600 A 192.0.2.53",
+                ),
+            ],
+            16,
+        );
+        let www: DomainName = "www.example.com.".parse().unwrap();
+        let mail: DomainName = "mail.example.com.".parse().unwrap();
+
+        resolver.resolve(&www, DnsType::A).unwrap();
+        assert_eq!(queries.borrow().len(), 3);
+        queries.borrow_mut().clear();
+
+        time.fetch_add(100, Ordering::Relaxed);
+        let answer = resolver.resolve(&www, DnsType::A).unwrap();
+        assert_eq!(answer.records[0].ttl, 200);
+        resolver.resolve(&mail, DnsType::A).unwrap();
+        assert_eq!(
+            print_queries(&queries),
+            vec!["192.0.2.53 mail.example.com. A"]
+        );
+        queries.borrow_mut().clear();
+
+        // example.com. expired, com. did not
+        time.fetch_add(3600, Ordering::Relaxed);
+        resolver.resolve(&mail, DnsType::A).unwrap();
+        assert_eq!(
+            print_queries(&queries),
+            vec![
+                "192.5.6.30 mail.example.com. A",
+                "192.0.2.53 mail.example.com. A"
+            ]
+        );
+    }
+
+    #[test]
+    fn case6() {
+        // NXDOMAIN and NODATA are cached for the smaller of the SOA TTL and MINIMUM
+        let soa =
+            "example.com. 3600 SOA ns.example.com. hostmaster.example.com. 1 7200 900 1209600 300";
+        let (resolver, queries, time) = create_resolver(
+            vec![
+                (
+                    "198.41.0.4",
+                    "nothing.example.com. A",
+                    DnsHeaderRcode::NoNameError,
+                    "",
+                    soa,
+                    "",
+                ),
+                (
+                    "198.41.0.4",
+                    "example.com. MX",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    &format!("{}\nexample.com. 3600 NS ns.example.com.", soa),
+                    "",
+                ),
+                (
+                    "198.41.0.4",
+                    "example.com. TXT",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "",
+                    "",
+                ),
+            ],
+            16,
+        );
+        let nothing: DomainName = "nothing.example.com.".parse().unwrap();
+        let example: DomainName = "example.com.".parse().unwrap();
+
+        let answer = resolver.resolve(&nothing, DnsType::A).unwrap();
+        assert_eq!(answer.rcode, DnsHeaderRcode::NoNameError);
+        let answer = resolver.resolve(&example, DnsType::MX).unwrap();
+        assert_eq!(answer.rcode, DnsHeaderRcode::NoError);
+        assert_eq!(answer.records, Vec::new());
+        // no SOA, nothing to cache
+        let answer = resolver.resolve(&example, DnsType::TXT).unwrap();
+        assert_eq!(answer.records, Vec::new());
+        assert_eq!(queries.borrow().len(), 3);
+        queries.borrow_mut().clear();
+
+        time.fetch_add(299, Ordering::Relaxed);
+        let answer = resolver.resolve(&nothing, DnsType::AAAA).unwrap();
+        assert_eq!(answer.rcode, DnsHeaderRcode::NoNameError);
+        let answer = resolver.resolve(&example, DnsType::MX).unwrap();
+        assert_eq!(answer.rcode, DnsHeaderRcode::NoError);
+        resolver.resolve(&example, DnsType::TXT).unwrap();
+        assert_eq!(print_queries(&queries), vec!["198.41.0.4 example.com. TXT"]);
+        queries.borrow_mut().clear();
+
+        time.fetch_add(1, Ordering::Relaxed);
+        resolver.resolve(&nothing, DnsType::A).unwrap();
+        resolver.resolve(&example, DnsType::MX).unwrap();
+        assert_eq!(
+            print_queries(&queries),
+            vec![
+                "198.41.0.4 nothing.example.com. A",
+                "198.41.0.4 example.com. MX"
+            ]
+        );
+    }
+
+    #[test]
+    fn case7() {
+        // a server that does not reply is retried, one that refuses is not, and a name server
+        // without glue is resolved last
+        let referral = "com. 172800 NS a.gtld-servers.com.\ncom. 172800 NS b.gtld-servers.com.\ncom. 172800 NS c.gtld-servers.net.";
+        let glue =
+            "a.gtld-servers.com. 172800 A 192.5.6.30\nb.gtld-servers.com. 172800 A 192.33.14.30";
+        let (resolver, queries, _) = create_resolver(
+            vec![
+                (
+                    "198.41.0.4",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    referral,
+                    glue,
+                ),
+                (
+                    "192.33.14.30",
+                    "www.example.com. A",
+                    DnsHeaderRcode::Refused,
+                    "",
+                    "",
+                    "",
+                ),
+                (
+                    "198.41.0.4",
+                    "c.gtld-servers.net. A",
+                    DnsHeaderRcode::NoError,
+                    "c.gtld-servers.net. 172800 A 192.26.92.30",
+                    "",
+                    "",
+                ),
+                (
+                    "192.26.92.30",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "www.example.com. 300 A 192.0.2.80",
+                    "",
+                    "",
+                ),
+            ],
+            16,
+        );
+
+        let answer = resolver
+            .resolve(&"www.example.com.".parse().unwrap(), DnsType::A)
+            .unwrap();
+        assert_eq!(answer.records.len(), 1);
+        assert_eq!(
+            print_queries(&queries),
+            vec![
+                "198.41.0.4 www.example.com. A",
+                "192.5.6.30 www.example.com. A",
+                "192.5.6.30 www.example.com. A",
+                "192.33.14.30 www.example.com. A",
+                "198.41.0.4 c.gtld-servers.net. A",
+                "192.26.92.30 www.example.com. A",
+            ]
+        );
+    }
+
+    #[test]
+    fn case8() {
+        // every server fails
+        let (resolver, queries, _) = create_resolver(
+            vec![
+                (
+                    "198.41.0.4",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "com. 172800 NS a.gtld-servers.com.\ncom. 172800 NS b.gtld-servers.com.",
+                    "a.gtld-servers.com. 172800 A 192.5.6.30\nb.gtld-servers.com. 172800 A 192.33.14.30",
+                ),
+                (
+                    "192.33.14.30",
+                    "www.example.com. A",
+                    DnsHeaderRcode::ServerFailure,
+                    "",
+                    "",
+                    "",
+                ),
+            ],
+            16,
+        );
+
+        let error = resolver
+            .resolve(&"www.example.com.".parse().unwrap(), DnsType::A)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            ResolveError::ServerFailure(
+                "every server of com. failed: 192.5.6.30: no reply from 192.5.6.30 to www.example.com.\tIN\tA, 192.33.14.30 replied ServerFailure".to_string()
+            )
+        );
+        assert_eq!(queries.borrow().len(), 4);
+    }
+
+    #[test]
+    fn case9() {
+        // the fastest known server is asked first, and the table lasts across queries
+        let (resolver, queries, _) = create_resolver(
+            vec![
+                (
+                    "198.41.0.4",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "com. 172800 NS a.gtld-servers.com.\ncom. 172800 NS b.gtld-servers.com.",
+                    "a.gtld-servers.com. 172800 A 192.5.6.30\nb.gtld-servers.com. 172800 A 192.33.14.30",
+                ),
+                (
+                    "192.33.14.30",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "www.example.com. 300 A 192.0.2.80",
+                    "",
+                    "",
+                ),
+            ],
+            16,
+        );
+        resolver
+            .servers
+            .borrow_mut()
+            .record_rtt("192.5.6.30", Duration::from_millis(200));
+        resolver
+            .servers
+            .borrow_mut()
+            .record_rtt("192.33.14.30", Duration::from_millis(20));
+
+        resolver
+            .resolve(&"www.example.com.".parse().unwrap(), DnsType::A)
+            .unwrap();
+        assert_eq!(
+            print_queries(&queries),
+            vec![
+                "198.41.0.4 www.example.com. A",
+                "192.33.14.30 www.example.com. A"
+            ]
+        );
+
+        let servers = resolver.servers();
+        assert_eq!(servers.get("192.5.6.30").unwrap().queries, 1);
+        assert_eq!(servers.get("192.33.14.30").unwrap().queries, 2);
+        assert_eq!(servers.get("198.41.0.4").unwrap().queries, 1);
+        assert!(servers.get("192.33.14.30").unwrap().srtt < Duration::from_millis(20));
+    }
+
+    #[test]
+    fn case10() {
+        // with 0x20 a server that does not echo the case three times in a row is asked again
+        // without it, and after that only without it
+        let (resolver, queries, _) = create_resolver_with(
+            vec![
+                (
+                    "198.41.0.4",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "com. 172800 NS a.gtld-servers.com.",
+                    "a.gtld-servers.com. 172800 A 192.5.6.30",
+                ),
+                (
+                    "192.5.6.30",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "www.example.com. 300 A 192.0.2.80",
+                    "",
+                    "",
+                ),
+                (
+                    "192.5.6.30",
+                    "mail.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "mail.example.com. 300 A 192.0.2.25",
+                    "",
+                    "",
+                ),
+            ],
+            |config, transport| {
+                config.case_randomization = true;
+                config.retries = 3;
+                transport.case_mangling_servers = vec!["192.5.6.30".to_string()];
+            },
+        );
+
+        let answer = resolver
+            .resolve(&"www.example.com.".parse().unwrap(), DnsType::A)
+            .unwrap();
+        assert_eq!(answer.records.len(), 1);
+        resolver
+            .resolve(&"mail.example.com.".parse().unwrap(), DnsType::A)
+            .unwrap();
+
+        let queries = queries.borrow();
+        let sent_to = |server: &str| {
+            return queries
+                .iter()
+                .filter(|(address, _)| address == server)
+                .map(|(_, question)| question.q_name.to_string())
+                .collect::<Vec<String>>();
+        };
+        // the root echoes, and was asked with random case
+        assert_eq!(sent_to("198.41.0.4").len(), 1);
+        let com = sent_to("192.5.6.30");
+        assert_eq!(com.len(), 5);
+        assert_eq!(com[3], "www.example.com.");
+        assert_eq!(com[4], "mail.example.com.");
+        assert!(resolver.servers().get("192.5.6.30").unwrap().mangles_case);
+        assert!(!resolver.servers().get("198.41.0.4").unwrap().mangles_case);
+    }
+
+    #[test]
+    fn case11() {
+        // glue and records outside the zone are discarded with a reason
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let log_lines = logs.clone();
+        let (resolver, _, _) = create_resolver_with(
+            vec![
+                (
+                    "198.41.0.4",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "com. 172800 NS a.gtld-servers.com.\ncom. 172800 NS ns.example.net.",
+                    "a.gtld-servers.com. 172800 A 192.5.6.30\nns.example.net. 172800 A 203.0.113.66",
+                ),
+                (
+                    "192.5.6.30",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "example.com. 3600 NS ns.example.com.\nother.com. 3600 NS ns.other.com.\norg. 3600 NS ns.example.org.",
+                    "ns.example.com. 3600 A 192.0.2.53\nns.example.org. 3600 A 203.0.113.66",
+                ),
+                (
+                    "192.0.2.53",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "www.example.com. 300 A 192.0.2.80\nwww.example.org. 300 A 203.0.113.66",
+                    "",
+                    "",
+                ),
+            ],
+            |config, _| {
+                config.log = Arc::new(move |line| log_lines.lock().unwrap().push(line.to_string()));
+            },
+        );
+
+        let answer = resolver
+            .resolve(&"www.example.com.".parse().unwrap(), DnsType::A)
+            .unwrap();
+        assert_eq!(answer.records.len(), 1);
+
+        let logs = logs.lock().unwrap();
+        let discards: Vec<&str> = logs
+            .iter()
+            .map(|line| line.trim_start())
+            .filter(|line| line.contains("discarded"))
+            .collect();
+        assert_eq!(
+            discards,
+            vec![
+                "[www.example.com. A in .] glue discarded, out of the delegated zone. name=ns.example.net. zone=com.",
+                "[www.example.com. A in com.] NS resource record in authority section discarded, out of zone. name=org. zone=com.",
+                "[www.example.com. A in com.] A resource record in additional section discarded, out of zone. name=ns.example.org. zone=com.",
+                "[www.example.com. A in com.] NS resource record discarded, www.example.com. is not in the zone. zone=other.com.",
+                "[www.example.com. A in example.com.] A resource record in answer section discarded, out of zone. name=www.example.org. zone=example.com.",
+            ]
+        );
+    }
+
+    #[test]
+    fn case12() {
+        // a CNAME loop ends with the chain limit, once the CNAMEs come from the cache
+        let (resolver, queries, _) = create_resolver(
+            vec![
+                (
+                    "198.41.0.4",
+                    "a.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "a.example.com. 300 CNAME b.example.com.",
+                    "",
+                    "",
+                ),
+                (
+                    "198.41.0.4",
+                    "b.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "b.example.com. 300 CNAME a.example.com.",
+                    "",
+                    "",
+                ),
+            ],
+            32,
+        );
+
+        let error = resolver
+            .resolve(&"a.example.com.".parse().unwrap(), DnsType::A)
+            .unwrap_err();
+        assert_eq!(error, ResolveError::CnameChainTooLong(9));
+        assert_eq!(
+            print_queries(&queries),
+            vec!["198.41.0.4 a.example.com. A", "198.41.0.4 b.example.com. A"]
+        );
+    }
+
+    #[test]
+    fn case13() {
+        // two zones served only by name servers in each other, without glue
+        let (resolver, queries, _) = create_resolver(
+            vec![
+                (
+                    "198.41.0.4",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "com. 172800 NS a.gtld-servers.com.",
+                    "a.gtld-servers.com. 172800 A 192.5.6.30",
+                ),
+                (
+                    "192.5.6.30",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "example.com. 172800 NS ns.example.net.",
+                    "",
+                ),
+                (
+                    "198.41.0.4",
+                    "ns.example.net. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "net. 172800 NS a.gtld-servers.net.",
+                    "a.gtld-servers.net. 172800 A 192.12.94.30",
+                ),
+                (
+                    "192.12.94.30",
+                    "ns.example.net. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "example.net. 172800 NS ns.example.com.",
+                    "",
+                ),
+            ],
+            32,
+        );
+
+        let error = resolver
+            .resolve(&"www.example.com.".parse().unwrap(), DnsType::A)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            ResolveError::LoopDetected(
+                "the address of ns.example.net. is needed to resolve itself".to_string()
+            )
+        );
+        assert_eq!(queries.borrow().len(), 4);
+    }
+
+    #[test]
+    fn case14() {
+        // a referral back to a server that was already asked
+        let (resolver, queries, _) = create_resolver(
+            vec![
+                (
+                    "198.41.0.4",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "com. 172800 NS a.gtld-servers.com.",
+                    "a.gtld-servers.com. 172800 A 192.5.6.30",
+                ),
+                (
+                    "192.5.6.30",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "com. 172800 NS a.gtld-servers.com.",
+                    "a.gtld-servers.com. 172800 A 192.5.6.30",
+                ),
+            ],
+            32,
+        );
+
+        let error = resolver
+            .resolve(&"www.example.com.".parse().unwrap(), DnsType::A)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            ResolveError::LoopDetected(
+                "192.5.6.30 asked about www.example.com. A again".to_string()
+            )
+        );
+        assert_eq!(queries.borrow().len(), 2);
+    }
+
+    #[test]
+    fn case15() {
+        // the referral limit and the query budget
+        let replies = || {
+            return vec![
+                (
+                    "198.41.0.4",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "com. 172800 NS a.gtld-servers.com.",
+                    "a.gtld-servers.com. 172800 A 192.5.6.30",
+                ),
+                (
+                    "192.5.6.30",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "example.com. 172800 NS ns.example.com.",
+                    "ns.example.com. 172800 A 192.0.2.53",
+                ),
+                (
+                    "192.0.2.53",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "www.example.com. 300 A 192.0.2.80",
+                    "",
+                    "",
+                ),
+            ];
+        };
+        let name: DomainName = "www.example.com.".parse().unwrap();
+
+        let (resolver, _, _) = create_resolver_with(replies(), |config, _| {
+            config.max_referrals = 1;
+        });
+        assert_eq!(
+            resolver.resolve(&name, DnsType::A).unwrap_err(),
+            ResolveError::TooManyReferrals(2)
+        );
+
+        let (resolver, queries, _) = create_resolver_with(replies(), |config, _| {
+            config.max_queries = 2;
+        });
+        assert_eq!(
+            resolver.resolve(&name, DnsType::A).unwrap_err(),
+            ResolveError::QueryBudgetExhausted(2)
+        );
+        assert_eq!(queries.borrow().len(), 2);
+
+        // the budget is for one resolution, the next one starts from the cached delegation
+        let answer = resolver.resolve(&name, DnsType::A).unwrap();
+        assert_eq!(answer.records.len(), 1);
+    }
+
+    #[test]
+    fn case16() {
+        // validated from the configured trust anchor, with the root keys fetched once
+        let root = Zone::new(".", 1);
+        let dnskey_reply = || {
+            let question = DnsQuestionSectionEntry {
+                q_name: DomainName::root(),
+                q_type: DnsType::DNSKEY,
+                q_class: DnsClass::IN,
+            };
+            let reply =
+                create_message(DnsHeaderRcode::NoError, root.sign_dnskeys(), vec![], vec![]);
+            return ("198.41.0.4".to_string(), question, reply);
+        };
+        let a_reply = |name: &str, address: Ipv4Addr| {
+            let question = DnsQuestionSectionEntry {
+                q_name: name.parse().unwrap(),
+                q_type: DnsType::A,
+                q_class: DnsClass::IN,
+            };
+            let a = create_rr(name, DnsType::A, RData::A(address));
+            let reply = create_message(
+                DnsHeaderRcode::NoError,
+                root.sign(vec![a], 2),
+                vec![],
+                vec![],
+            );
+            return ("198.41.0.4".to_string(), question, reply);
+        };
+        let (resolver, queries, _) = create_resolver_with(vec![], |config, transport| {
+            config.dnssec = true;
+            config.trust_anchors = vec![root.ds()];
+            transport.replies = vec![
+                dnskey_reply(),
+                a_reply("www.example.", Ipv4Addr::new(192, 0, 2, 80)),
+                a_reply("mail.example.", Ipv4Addr::new(192, 0, 2, 25)),
+            ];
+        });
+
+        for name in ["www.example.", "mail.example."] {
+            let answer = resolver
+                .resolve(&name.parse().unwrap(), DnsType::A)
+                .unwrap();
+            assert_eq!(answer.status, ValidationStatus::Secure);
+            assert_eq!(answer.records.len(), 1);
+        }
+        assert_eq!(
+            print_queries(&queries),
+            vec![
+                "198.41.0.4 . DNSKEY",
+                "198.41.0.4 www.example. A",
+                "198.41.0.4 mail.example. A",
+            ]
+        );
+
+        // the keys do not match another anchor
+        let other = Zone::new(".", 2);
+        let (resolver, _, _) = create_resolver_with(vec![], |config, transport| {
+            config.dnssec = true;
+            config.trust_anchors = vec![other.ds()];
+            transport.replies = vec![
+                dnskey_reply(),
+                a_reply("www.example.", Ipv4Addr::new(192, 0, 2, 80)),
+            ];
+        });
+        let answer = resolver
+            .resolve(&"www.example.".parse().unwrap(), DnsType::A)
+            .unwrap();
+        assert_eq!(answer.status, ValidationStatus::Bogus);
+    }
+
+    #[test]
+    fn case17() {
+        // the root servers serve arpa. too, so a referral from them may be signed by arpa.
+        let root = Zone::new(".", 1);
+        let arpa = Zone::new("arpa.", 2);
+        let in_addr_arpa = Zone::new("in-addr.arpa.", 3);
+        let reply = |server: &str,
+                     name: &str,
+                     q_type: DnsType,
+                     answers: Vec<DnsResourceRecord>,
+                     authorities: Vec<DnsResourceRecord>,
+                     additionals: Vec<DnsResourceRecord>| {
+            let question = DnsQuestionSectionEntry {
+                q_name: name.parse().unwrap(),
+                q_type,
+                q_class: DnsClass::IN,
+            };
+            let mut message =
+                create_message(DnsHeaderRcode::NoError, answers, authorities, additionals);
+            message.questions = vec![question.clone()];
+            return (server.to_string(), question, message);
+        };
+        let mut referral = vec![create_rr(
+            "in-addr.arpa.",
+            DnsType::NS,
+            RData::NS("ns.in-addr.arpa.".parse().unwrap()),
+        )];
+        referral.extend(arpa.sign(
+            vec![create_rr("in-addr.arpa.", DnsType::DS, in_addr_arpa.ds())],
+            2,
+        ));
+        let ptr = create_rr(
+            "1.2.0.192.in-addr.arpa.",
+            DnsType::PTR,
+            RData::PTR("www.example.com.".parse().unwrap()),
+        );
+
+        let (resolver, queries, _) = create_resolver_with(vec![], |config, transport| {
+            config.dnssec = true;
+            config.trust_anchors = vec![root.ds()];
+            transport.replies = vec![
+                reply(
+                    "198.41.0.4",
+                    ".",
+                    DnsType::DNSKEY,
+                    root.sign_dnskeys(),
+                    vec![],
+                    vec![],
+                ),
+                reply(
+                    "198.41.0.4",
+                    "1.2.0.192.in-addr.arpa.",
+                    DnsType::PTR,
+                    vec![],
+                    referral,
+                    vec![create_rr(
+                        "ns.in-addr.arpa.",
+                        DnsType::A,
+                        RData::A(Ipv4Addr::new(192, 0, 2, 53)),
+                    )],
+                ),
+                reply(
+                    "198.41.0.4",
+                    "arpa.",
+                    DnsType::DS,
+                    root.sign(vec![create_rr("arpa.", DnsType::DS, arpa.ds())], 1),
+                    vec![],
+                    vec![],
+                ),
+                reply(
+                    "198.41.0.4",
+                    "arpa.",
+                    DnsType::DNSKEY,
+                    arpa.sign_dnskeys(),
+                    vec![],
+                    vec![],
+                ),
+                reply(
+                    "192.0.2.53",
+                    "in-addr.arpa.",
+                    DnsType::DNSKEY,
+                    in_addr_arpa.sign_dnskeys(),
+                    vec![],
+                    vec![],
+                ),
+                reply(
+                    "192.0.2.53",
+                    "1.2.0.192.in-addr.arpa.",
+                    DnsType::PTR,
+                    in_addr_arpa.sign(vec![ptr], 6),
+                    vec![],
+                    vec![],
+                ),
+            ];
+        });
+
+        let answer = resolver
+            .resolve(&"1.2.0.192.in-addr.arpa.".parse().unwrap(), DnsType::PTR)
+            .unwrap();
+        assert_eq!(answer.status, ValidationStatus::Secure);
+        assert_eq!(answer.records.len(), 1);
+        assert_eq!(
+            print_queries(&queries),
+            vec![
+                "198.41.0.4 . DNSKEY",
+                "198.41.0.4 1.2.0.192.in-addr.arpa. PTR",
+                "198.41.0.4 arpa. DS",
+                "198.41.0.4 arpa. DNSKEY",
+                "192.0.2.53 in-addr.arpa. DNSKEY",
+                "192.0.2.53 1.2.0.192.in-addr.arpa. PTR",
+            ]
+        );
+    }
+    #[test]
+    fn case18() {
+        // an exploration rate that is not a probability
+        for exploration in [-0.5, 1.5, f64::NAN] {
+            let config = ResolverConfig {
+                exploration,
+                ..Default::default()
+            };
+            assert!(
+                Resolver::new(config)
+                    .err()
+                    .unwrap()
+                    .starts_with("exploration out of 0.0 to 1.0: ")
+            );
+
+            let (resolver, _, _) = create_resolver_with(Vec::new(), |config, _| {
+                config.exploration = exploration;
+            });
+            assert!((0.0..=1.0).contains(&resolver.config().exploration));
+        }
+    }
+    #[test]
+    fn case19() {
+        // every query is sent with its own id, retries and failovers too
+        let ids = Rc::new(RefCell::new(Vec::new()));
+        let (resolver, queries, _) = create_resolver_with(
+            vec![(
+                "199.9.14.201",
+                "example.com. A",
+                DnsHeaderRcode::NoError,
+                "example.com. 300 A 192.0.2.1",
+                "",
+                "",
+            )],
+            |config, transport| {
+                config.root_hints = vec!["198.41.0.4".to_string(), "199.9.14.201".to_string()];
+                transport.ids = ids.clone();
+            },
+        );
+
+        let answer = resolver
+            .resolve(&"example.com.".parse().unwrap(), DnsType::A)
+            .unwrap();
+        assert_eq!(answer.records.len(), 1);
+        assert_eq!(
+            print_queries(&queries),
+            vec![
+                "198.41.0.4 example.com. A",
+                "198.41.0.4 example.com. A",
+                "199.9.14.201 example.com. A",
+            ]
+        );
+        let ids = ids.borrow();
+        assert_eq!(ids.iter().collect::<HashSet<&u16>>().len(), 3);
+    }
+    #[test]
+    fn case20() {
+        // name servers that share an address that fails are failed over, not taken for a loop
+        let (resolver, queries, _) = create_resolver(
+            vec![
+                (
+                    "198.41.0.4",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "",
+                    "com. 172800 NS a.gtld-servers.com.\ncom. 172800 NS b.gtld-servers.com.\ncom. 172800 NS c.gtld-servers.com.",
+                    "a.gtld-servers.com. 172800 A 192.5.6.30\nb.gtld-servers.com. 172800 A 192.5.6.30\nc.gtld-servers.com. 172800 A 192.26.92.30",
+                ),
+                (
+                    "192.26.92.30",
+                    "www.example.com. A",
+                    DnsHeaderRcode::NoError,
+                    "www.example.com. 300 A 192.0.2.80",
+                    "",
+                    "",
+                ),
+            ],
+            16,
+        );
+
+        let answer = resolver
+            .resolve(&"www.example.com.".parse().unwrap(), DnsType::A)
+            .unwrap();
+        assert_eq!(answer.records.len(), 1);
+        assert_eq!(
+            print_queries(&queries),
+            vec![
+                "198.41.0.4 www.example.com. A",
+                "192.5.6.30 www.example.com. A",
+                "192.5.6.30 www.example.com. A",
+                "192.26.92.30 www.example.com. A",
+            ]
+        );
+    }
+}
@@ -0,0 +1,32 @@
+//! Iterative resolution from the root servers.
+//!
+//! A [`Resolver`] sends its queries through a [`Transport`], by default a [`TapTransport`]
+//! that builds its own ethernet frames on a tap device. Several resolvers with different
+//! configs can run in one process.
+//!
+//! ```no_run
+//! use rust_dns::{dns::*, resolver::*};
+//!
+//! let config = ResolverConfig {
+//!     dnssec: true,
+//!     format: MessageFormat::Dig,
+//!     ..Default::default()
+//! };
+//! let resolver = Resolver::new(config).unwrap();
+//! let answer = resolver.resolve(&"example.com.".parse().unwrap(), DnsType::A).unwrap();
+//! println!("{} {}", answer.rcode, answer.status);
+//! ```
+mod cache;
+mod config;
+mod error;
+#[cfg(test)]
+mod fixtures;
+mod iterative;
+mod servers;
+mod transport;
+
+pub use self::config::*;
+pub use self::error::*;
+pub use self::iterative::*;
+pub use self::servers::*;
+pub use self::transport::*;
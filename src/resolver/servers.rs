@@ -0,0 +1,219 @@
+#![allow(clippy::needless_return)]
+
+use core::fmt;
+use std::{collections::HashMap, time::Duration};
+
+use rand::{random_bool, random_range};
+
+// what a server that was never asked is assumed to take, so that it is tried before slow ones
+const UNKNOWN_SRTT: Duration = Duration::from_millis(376);
+const MAX_SRTT: Duration = Duration::from_secs(120);
+// replies in a row that do not echo the case of the question before the server is asked without
+// 0x20. A single one may be forged.
+const MAX_CASE_MISMATCHES: u32 = 3;
+
+/// What the resolver knows about one name server.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct ServerStats {
+    // smoothed round trip time, penalized on failures
+    pub srtt: Duration,
+    pub queries: u32,
+    // timeouts and replies with an error other than NXDOMAIN
+    pub failures: u32,
+    // replies in a row that did not echo the case of the question
+    pub case_mismatches: u32,
+    // the server does not echo the case of the question, so it is asked without 0x20
+    pub mangles_case: bool,
+}
+
+/// Round trip times and failures of the name servers by address, kept across queries, and the
+/// servers that do not echo the case of the question.
+///
+/// ```
+/// use std::time::Duration;
+/// use rust_dns::resolver::ServerTable;
+///
+/// let mut servers = ServerTable::new();
+/// servers.record_rtt("192.0.2.1", Duration::from_millis(80));
+/// servers.record_rtt("192.0.2.2", Duration::from_millis(20));
+/// let addresses = ["192.0.2.1".to_string(), "192.0.2.2".to_string()];
+/// assert_eq!(servers.order(&addresses, 0.0), vec!["192.0.2.2", "192.0.2.1"]);
+/// ```
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct ServerTable {
+    servers: HashMap<String, ServerStats>,
+}
+impl ServerTable {
+    pub fn new() -> Self {
+        return ServerTable {
+            servers: HashMap::new(),
+        };
+    }
+
+    pub fn get(&self, address: &str) -> Option<&ServerStats> {
+        return self.servers.get(address);
+    }
+
+    fn entry(&mut self, address: &str) -> &mut ServerStats {
+        return self
+            .servers
+            .entry(address.to_string())
+            .or_insert(ServerStats {
+                srtt: UNKNOWN_SRTT,
+                queries: 0,
+                failures: 0,
+                case_mismatches: 0,
+                mangles_case: false,
+            });
+    }
+
+    // the first measurement is taken as is, later ones move the average by an eighth.
+    // see: https://www.rfc-editor.org/rfc/rfc6298#section-2
+    pub fn record_rtt(&mut self, address: &str, rtt: Duration) {
+        let stats = self.entry(address);
+        stats.srtt = if stats.queries == 0 {
+            rtt
+        } else {
+            (stats.srtt * 7 + rtt) / 8
+        };
+        stats.queries += 1;
+    }
+
+    // a server that failed counts as at least as slow as the timeout, twice as slow for every
+    // further failure
+    pub fn record_failure(&mut self, address: &str, timeout: Duration) {
+        let stats = self.entry(address);
+        stats.srtt = (stats.srtt * 2).max(timeout).min(MAX_SRTT);
+        stats.queries += 1;
+        stats.failures += 1;
+    }
+
+    // the server is marked as mangling the case after a few mismatches in a row
+    pub fn record_case_mismatch(&mut self, address: &str) {
+        let stats = self.entry(address);
+        stats.case_mismatches += 1;
+        if stats.case_mismatches >= MAX_CASE_MISMATCHES {
+            stats.mangles_case = true;
+        }
+    }
+
+    pub fn record_case_echoed(&mut self, address: &str) {
+        self.entry(address).case_mismatches = 0;
+    }
+
+    pub fn mangles_case(&self, address: &str) -> bool {
+        return self
+            .servers
+            .get(address)
+            .is_some_and(|stats| stats.mangles_case);
+    }
+
+    // each address once, the fastest server first. Now and then another one is moved to the
+    // front, so that servers that were slow once get measured again.
+    pub fn order(&self, addresses: &[String], exploration: f64) -> Vec<String> {
+        let srtt = |address: &String| {
+            return self
+                .servers
+                .get(address)
+                .map_or(UNKNOWN_SRTT, |stats| stats.srtt);
+        };
+
+        // name servers of a zone may share an address
+        let mut ordered: Vec<String> = Vec::new();
+        for address in addresses {
+            if !ordered.contains(address) {
+                ordered.push(address.clone());
+            }
+        }
+        ordered.sort_by_key(srtt);
+        if ordered.len() > 1 && random_bool(exploration) {
+            let explored = ordered.remove(random_range(1..ordered.len()));
+            ordered.insert(0, explored);
+        }
+
+        return ordered;
+    }
+}
+impl fmt::Display for ServerTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut servers: Vec<(&String, &ServerStats)> = self.servers.iter().collect();
+        servers.sort_by_key(|(address, stats)| (stats.srtt, *address));
+
+        for (i, (address, stats)) in servers.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{}\tsrtt={}ms\tqueries={}\tfailures={}",
+                address,
+                stats.srtt.as_millis(),
+                stats.queries,
+                stats.failures
+            )?;
+            if stats.mangles_case {
+                write!(f, "\tmangles_case")?;
+            }
+        }
+
+        write!(f, "")
+    }
+}
+#[cfg(test)]
+mod server_table {
+    use std::time::Duration;
+
+    use crate::resolver::servers::ServerTable;
+
+    #[test]
+    fn case1() {
+        let mut servers = ServerTable::new();
+        servers.record_rtt("192.0.2.1", Duration::from_millis(80));
+        servers.record_rtt("192.0.2.1", Duration::from_millis(160));
+        assert_eq!(
+            servers.get("192.0.2.1").unwrap().srtt,
+            Duration::from_millis(90)
+        );
+
+        servers.record_rtt("192.0.2.2", Duration::from_millis(20));
+        servers.record_failure("192.0.2.2", Duration::from_secs(3));
+        assert_eq!(
+            servers.get("192.0.2.2").unwrap().srtt,
+            Duration::from_secs(3)
+        );
+        servers.record_failure("192.0.2.2", Duration::from_secs(3));
+        assert_eq!(
+            servers.get("192.0.2.2").unwrap().srtt,
+            Duration::from_secs(6)
+        );
+
+        // unknown servers come before slow ones and keep their order
+        let addresses: Vec<String> = ["192.0.2.2", "192.0.2.3", "192.0.2.1", "192.0.2.4"]
+            .iter()
+            .map(|address| address.to_string())
+            .collect();
+        assert_eq!(
+            servers.order(&addresses, 0.0),
+            vec!["192.0.2.1", "192.0.2.3", "192.0.2.4", "192.0.2.2"]
+        );
+        assert_ne!(servers.order(&addresses, 1.0)[0], "192.0.2.1");
+        let shared = ["192.0.2.3".to_string(), "192.0.2.3".to_string()];
+        assert_eq!(servers.order(&shared, 1.0), vec!["192.0.2.3"]);
+
+        // a reply that echoes the case starts the count again
+        servers.record_case_mismatch("192.0.2.2");
+        servers.record_case_mismatch("192.0.2.2");
+        servers.record_case_echoed("192.0.2.2");
+        servers.record_case_mismatch("192.0.2.2");
+        servers.record_case_mismatch("192.0.2.2");
+        assert!(!servers.mangles_case("192.0.2.2"));
+        servers.record_case_mismatch("192.0.2.2");
+        assert!(servers.mangles_case("192.0.2.2"));
+        assert!(!servers.mangles_case("192.0.2.1"));
+        assert!(!servers.mangles_case("192.0.2.3"));
+        assert_eq!(
+            servers.to_string(),
+            "192.0.2.1\tsrtt=90ms\tqueries=2\tfailures=0\n192.0.2.2\tsrtt=6000ms\tqueries=3\tfailures=2\tmangles_case"
+        );
+    }
+}
@@ -0,0 +1,435 @@
+#![allow(clippy::needless_return)]
+
+use std::{
+    ops::RangeInclusive,
+    thread,
+    time::{Duration, Instant},
+};
+
+use rand::random_range;
+use tun_tap::{Iface, Mode};
+
+use super::config::{LogSink, ResolverConfig};
+use crate::{
+    dns::{DnsMessage, parse_dns_message},
+    net::*,
+};
+
+/// How queries reach name servers, so that resolution can run over other links or scripted
+/// replies.
+pub trait Transport {
+    /// Sends the query once to the server at `server_ipaddr` and returns the reply with the same
+    /// id, or an error when none comes in time. With `exact_case`, a reply that echoes the
+    /// question only up to case is held back while waiting for one that echoes it exactly, and
+    /// returned when none comes in time.
+    fn exchange(
+        &self,
+        query: &DnsMessage,
+        server_ipaddr: &str,
+        exact_case: bool,
+    ) -> Result<DnsMessage, String>;
+}
+
+/// Whether the reply echoes the names of the questions of the query letter for letter.
+/// see: https://datatracker.ietf.org/doc/html/draft-vixie-dnsext-dns0x20-00#section-4
+pub fn echoes_case(reply: &DnsMessage, query: &DnsMessage) -> bool {
+    return reply.questions.len() == query.questions.len()
+        && reply
+            .questions
+            .iter()
+            .zip(&query.questions)
+            .all(|(a, b)| a.q_name.eq_case_sensitive(&b.q_name));
+}
+#[cfg(test)]
+mod echoes_case {
+    use crate::{
+        dns::{DnsClass, DnsType, QueryBuilder},
+        resolver::transport::echoes_case,
+    };
+
+    #[test]
+    fn case1() {
+        let query = QueryBuilder::new(1)
+            .question(
+                &"wWw.ExAmple.com.".parse().unwrap(),
+                DnsType::A,
+                DnsClass::IN,
+            )
+            .build();
+        let mut reply = query.clone();
+        assert!(echoes_case(&reply, &query));
+
+        reply.questions[0].q_name = "www.example.com.".parse().unwrap();
+        assert!(!echoes_case(&reply, &query));
+
+        reply.questions.clear();
+        assert!(!echoes_case(&reply, &query));
+    }
+}
+
+/// Asks for the MAC address of `ipaddr_str` with ARP, waiting `config.timeout` for a reply and
+/// asking `config.retries` more times before giving up.
+pub fn resolve_macaddr(
+    ipaddr_str: &str,
+    iface: &Iface,
+    config: &ResolverConfig,
+) -> Result<String, String> {
+    let arp_message = create_arp_request_message(&config.my_ipaddr, &config.my_macaddr, ipaddr_str);
+    let arp_frame = create_ethernet_frame(
+        0x0806, // arp
+        "ff:ff:ff:ff:ff:ff",
+        &config.my_macaddr,
+        &arp_message,
+    );
+
+    for attempt in 0..=config.retries {
+        if attempt > 0 {
+            (config.log)(&format!("retrying arp request... attempt={}", attempt + 1));
+        }
+        (config.log)("send arp request...");
+        iface
+            .send(&arp_frame)
+            .map_err(|error| format!("failed to send arp request: {}", error))?;
+        let deadline = Instant::now() + config.timeout;
+
+        while Instant::now() < deadline {
+            let mut frame = vec![0; 1500];
+            let Ok(size) = iface.recv(&mut frame) else {
+                thread::sleep(Duration::from_millis(10));
+                continue;
+            };
+            frame.truncate(size);
+
+            if is_arp_reply(&frame, &config.my_ipaddr, &config.my_macaddr) {
+                (config.log)("arp reply received.");
+                let message = get_ethernet_frame_data(&frame);
+                return Ok(print_macaddr(&message[8..8 + 6].to_vec()));
+            }
+        }
+    }
+
+    return Err(format!(
+        "no arp reply from {} after {} attempts",
+        ipaddr_str,
+        config.retries as u32 + 1
+    ));
+}
+
+/// UDP over a tap device, with hand-built ethernet and IPv4 headers. Every query goes to the
+/// gateway, whose MAC address is resolved once with ARP.
+pub struct TapTransport {
+    iface: Iface,
+    my_ipaddr: String,
+    my_macaddr: String,
+    source_ports: RangeInclusive<u16>,
+    gateway_ipaddr: String,
+    gateway_macaddr: String,
+    timeout: Duration,
+    log: LogSink,
+}
+impl TapTransport {
+    pub fn new(config: &ResolverConfig) -> Result<Self, String> {
+        let iface = Iface::without_packet_info(&config.iface_name, Mode::Tap)
+            .map_err(|error| format!("failed to create a tap device: {}", error))?;
+        iface
+            .set_non_blocking()
+            .map_err(|error| format!("failed to set non blocking: {}", error))?;
+
+        let gateway_macaddr = resolve_macaddr(&config.gateway_ipaddr, &iface, config)?;
+        (config.log)(&format!("gateway_macaddr resolved: {}", gateway_macaddr));
+
+        return Ok(TapTransport {
+            iface,
+            my_ipaddr: config.my_ipaddr.clone(),
+            my_macaddr: config.my_macaddr.clone(),
+            source_ports: config.source_ports.clone(),
+            gateway_ipaddr: config.gateway_ipaddr.clone(),
+            gateway_macaddr,
+            timeout: config.timeout,
+            log: config.log.clone(),
+        });
+    }
+}
+impl Transport for TapTransport {
+    // every query goes out from a random port. arp requests are answered while waiting for the
+    // reply.
+    fn exchange(
+        &self,
+        query: &DnsMessage,
+        server_ipaddr: &str,
+        exact_case: bool,
+    ) -> Result<DnsMessage, String> {
+        let my_udp_port = random_range(self.source_ports.clone());
+        let dns_message = query
+            .to_wire()
+            .map_err(|error| format!("failed to write dns request: {}", error))?;
+        let dns_udp_datagram = create_udp_datagram(
+            my_udp_port,
+            53,
+            &dns_message,
+            &self.my_ipaddr,
+            server_ipaddr,
+        );
+        let dns_ip_packet = create_ip_packet(
+            17, // udp
+            query.header.id,
+            &self.my_ipaddr,
+            server_ipaddr,
+            &dns_udp_datagram,
+        );
+        let dns_ethernet_frame = create_ethernet_frame(
+            0x0800, // ipv4
+            &self.gateway_macaddr,
+            &self.my_macaddr,
+            &dns_ip_packet,
+        );
+
+        self.iface
+            .send(&dns_ethernet_frame)
+            .map_err(|error| format!("failed to send dns request: {}", error))?;
+        let deadline = Instant::now() + self.timeout;
+        // a forged reply does not know the case of the question, so the real one may still come
+        let mut case_mismatch = None;
+
+        loop {
+            if Instant::now() >= deadline {
+                if let Some(message) = case_mismatch {
+                    return Ok(message);
+                }
+                return Err(format!("timed out after {:?}", self.timeout));
+            }
+
+            let mut frame = vec![0; 1500];
+            let Ok(size) = self.iface.recv(&mut frame) else {
+                thread::sleep(Duration::from_millis(10));
+                continue;
+            };
+            // only the received bytes, so that the lengths in the headers are checked against them
+            frame.truncate(size);
+
+            //  arp
+            if is_arp_request(&frame, &self.my_ipaddr, &self.my_macaddr) {
+                (self.log)("arp request received, send arp reply.");
+
+                let arp_message = create_arp_reply_message(
+                    &self.my_ipaddr,
+                    &self.my_macaddr,
+                    &self.gateway_ipaddr,
+                    &self.gateway_macaddr,
+                );
+                let arp_frame = create_ethernet_frame(
+                    0x0806, // arp
+                    &self.gateway_macaddr,
+                    &self.my_macaddr,
+                    &arp_message,
+                );
+                self.iface
+                    .send(&arp_frame)
+                    .map_err(|error| format!("failed to send arp reply: {}", error))?;
+
+                continue;
+            }
+
+            //  dns
+            match parse_reply(
+                &frame,
+                query,
+                server_ipaddr,
+                &self.my_ipaddr,
+                &self.my_macaddr,
+                my_udp_port,
+            ) {
+                Ok(message) if !exact_case || echoes_case(&message, query) => return Ok(message),
+                Ok(message) => {
+                    (self.log)("unexpected case of the question, waiting for another reply...");
+                    case_mismatch.get_or_insert(message);
+                }
+                Err(Some(reason)) => (self.log)(&format!("{}, reply discarded.", reason)),
+                Err(None) => {}
+            }
+        }
+    }
+}
+
+// the reply to the query, when the frame is one. Replies that do not match the query in
+// every field an off-path attacker would have to guess are discarded with a reason.
+// see: https://www.rfc-editor.org/rfc/rfc5452#section-4
+fn parse_reply(
+    frame: &Vec<u8>,
+    query: &DnsMessage,
+    server_ipaddr: &str,
+    my_ipaddr: &str,
+    my_macaddr: &str,
+    my_udp_port: u16,
+) -> Result<DnsMessage, Option<String>> {
+    //// destination is my macaddr and type is ip
+    if frame.len() < 14 + 20 + 8
+        || frame[0..6] != parse_macaddr(my_macaddr)
+        || frame[12..12 + 2] != [0x08_u8, 0x00_u8]
+    {
+        return Err(None);
+    }
+    let ip_packet = get_ethernet_frame_data(frame);
+    if get_ip_packet_protocol(&ip_packet) != 17
+        || get_ip_packet_dest_ipaddr(&ip_packet) != my_ipaddr
+    {
+        return Err(None);
+    }
+
+    let src_ipaddr = get_ip_packet_src_ipaddr(&ip_packet);
+    if src_ipaddr != server_ipaddr {
+        return Err(Some(format!("unexpected source address {}", src_ipaddr)));
+    }
+    let udp_datagram =
+        get_ip_packet_data(&ip_packet).ok_or_else(|| Some("malformed ip packet".to_string()))?;
+    let dns_message = get_udp_datagram_data(&udp_datagram)
+        .ok_or_else(|| Some("malformed udp datagram".to_string()))?;
+    let src_port = get_udp_datagram_src_port(&udp_datagram);
+    if src_port != 53 {
+        return Err(Some(format!("unexpected source port {}", src_port)));
+    }
+    let dest_port = get_udp_datagram_dest_port(&udp_datagram);
+    if dest_port != my_udp_port {
+        return Err(Some(format!("unexpected destination port {}", dest_port)));
+    }
+
+    let message = parse_dns_message(&dns_message)
+        .map_err(|error| Some(format!("unparsable message ({})", error)))?;
+    if message.header.id != query.header.id {
+        return Err(Some(format!("unexpected id {}", message.header.id)));
+    }
+    if !message.header.qr || message.questions != query.questions {
+        return Err(Some("unexpected question".to_string()));
+    }
+
+    return Ok(message);
+}
+#[cfg(test)]
+mod parse_reply {
+    use crate::{
+        dns::{DnsClass, DnsMessage, DnsType, QueryBuilder},
+        net::*,
+        resolver::transport::parse_reply,
+    };
+
+    fn create_frame(
+        message: &DnsMessage,
+        src_ipaddr: &str,
+        src_port: u16,
+        dest_port: u16,
+    ) -> Vec<u8> {
+        let datagram = create_udp_datagram(
+            src_port,
+            dest_port,
+            &message.to_wire().unwrap(),
+            src_ipaddr,
+            "192.168.70.2",
+        );
+        let packet = create_ip_packet(17, 1, src_ipaddr, "192.168.70.2", &datagram);
+        return create_ethernet_frame(0x0800, "00:00:5e:00:53:01", "00:00:5e:00:53:02", &packet);
+    }
+
+    #[test]
+    fn case1() {
+        let query = QueryBuilder::new(0xbeef)
+            .question(&"example.com.".parse().unwrap(), DnsType::A, DnsClass::IN)
+            .build();
+        let mut reply = query.clone();
+        reply.header.qr = true;
+        let parse = |frame: &Vec<u8>| {
+            return parse_reply(
+                frame,
+                &query,
+                "192.0.2.53",
+                "192.168.70.2",
+                "00:00:5e:00:53:01",
+                40000,
+            );
+        };
+
+        assert_eq!(
+            parse(&create_frame(&reply, "192.0.2.53", 53, 40000)),
+            Ok(reply.clone())
+        );
+        assert_eq!(
+            parse(&create_frame(&reply, "192.0.2.54", 53, 40000)),
+            Err(Some("unexpected source address 192.0.2.54".to_string()))
+        );
+        assert_eq!(
+            parse(&create_frame(&reply, "192.0.2.53", 5353, 40000)),
+            Err(Some("unexpected source port 5353".to_string()))
+        );
+        assert_eq!(
+            parse(&create_frame(&reply, "192.0.2.53", 53, 40001)),
+            Err(Some("unexpected destination port 40001".to_string()))
+        );
+
+        let mut forged = reply.clone();
+        forged.header.id = 0xbeee;
+        assert_eq!(
+            parse(&create_frame(&forged, "192.0.2.53", 53, 40000)),
+            Err(Some("unexpected id 48878".to_string()))
+        );
+        let mut forged = reply.clone();
+        forged.questions[0].q_type = DnsType::AAAA;
+        assert_eq!(
+            parse(&create_frame(&forged, "192.0.2.53", 53, 40000)),
+            Err(Some("unexpected question".to_string()))
+        );
+
+        // not for us
+        let frame = create_frame(&reply, "192.0.2.53", 53, 40000);
+        let mut other_mac = frame.clone();
+        other_mac[5] = 0x02;
+        assert_eq!(parse(&other_mac), Err(None));
+    }
+
+    #[test]
+    fn case2() {
+        let query = QueryBuilder::new(0xbeef)
+            .question(&"example.com.".parse().unwrap(), DnsType::A, DnsClass::IN)
+            .build();
+        let mut reply = query.clone();
+        reply.header.qr = true;
+        let parse = |frame: &Vec<u8>| {
+            return parse_reply(
+                frame,
+                &query,
+                "192.0.2.53",
+                "192.168.70.2",
+                "00:00:5e:00:53:01",
+                40000,
+            );
+        };
+        let frame = create_frame(&reply, "192.0.2.53", 53, 40000);
+        let malformed_ip_packet = Err(Some("malformed ip packet".to_string()));
+        let malformed_udp_datagram = Err(Some("malformed udp datagram".to_string()));
+
+        // header length below the minimum
+        let mut forged = frame.clone();
+        forged[14] = 0x44;
+        assert_eq!(parse(&forged), malformed_ip_packet);
+        // total length below the header length
+        let mut forged = frame.clone();
+        forged[14 + 2..14 + 4].copy_from_slice(&16_u16.to_be_bytes());
+        assert_eq!(parse(&forged), malformed_ip_packet);
+        // total length beyond the frame
+        let mut forged = frame.clone();
+        forged[14 + 2..14 + 4].copy_from_slice(&1500_u16.to_be_bytes());
+        assert_eq!(parse(&forged), malformed_ip_packet);
+        // truncated frame
+        assert_eq!(
+            parse(&frame[..frame.len() - 1].to_vec()),
+            malformed_ip_packet
+        );
+
+        // udp length below the header length
+        let mut forged = frame.clone();
+        forged[14 + 20 + 4..14 + 20 + 6].copy_from_slice(&4_u16.to_be_bytes());
+        assert_eq!(parse(&forged), malformed_udp_datagram);
+        // udp length beyond the ip packet
+        let mut forged = frame.clone();
+        forged[14 + 20 + 4..14 + 20 + 6].copy_from_slice(&1400_u16.to_be_bytes());
+        assert_eq!(parse(&forged), malformed_udp_datagram);
+    }
+}